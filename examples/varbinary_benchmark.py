@@ -0,0 +1,41 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+# Measure building the varbinary values from the bytes-like objects, which is
+# copied at once, against the list of ints, which is converted element by
+# element. No server is needed.
+
+import timeit
+from horaedb_client import ValueBuilder
+
+BLOB_BYTES = 16 << 20
+ROUNDS = 5
+
+
+def bench(name, val):
+    builder = ValueBuilder()
+    seconds = min(timeit.repeat(lambda: builder.varbinary(val), number=1, repeat=ROUNDS))
+    print(f"{name:<24}{seconds * 1000:>10.2f} ms{BLOB_BYTES / seconds / (1 << 30):>10.2f} GiB/s")
+
+
+if __name__ == "__main__":
+    blob = bytes(i % 251 for i in range(BLOB_BYTES))
+    bench("bytes", blob)
+    bench("bytearray", bytearray(blob))
+    bench("memoryview", memoryview(blob))
+    bench("memoryview (strided)", memoryview(blob + blob)[::2])
+    bench("list of ints", list(blob))
@@ -15,22 +15,73 @@
 # specific language governing permissions and limitations
 # under the License.
 
+import datetime
+import decimal
 import enum
-from typing import Any, List, Optional
+from typing import (Any, Awaitable, Dict, List, Optional, Tuple, Union,
+                    overload)
+
+__version__: str
+
+
+def features() -> Dict[str, bool]: ...
 
 # models
 
 
 class SqlQueryRequest:
     def __init__(self, tables: List[str], sql: str): ...
+    @staticmethod
+    def with_params(tables: List[str], sql_template: str,
+                    params: List[Value]) -> SqlQueryRequest: ...
+    def paginate(self, limit: int, offset: int = 0) -> SqlQueryRequest: ...
+    @property
+    def tables(self) -> List[str]: ...
+    @property
+    def sql(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[List[str], str]]: ...
 
 
 class SqlQueryResponse:
     def num_rows(self) -> int: ...
+    def is_query(self) -> bool: ...
+    def is_mutation(self) -> bool: ...
     def row_by_idx(self, idx: int) -> Optional[Row]: ...
     def iter_rows(self) -> RowIter: ...
+    def __iter__(self) -> RowIter: ...
+    def iter_dicts(self) -> DictIter: ...
+    def __len__(self) -> int: ...
+    def __bool__(self) -> bool: ...
+    @overload
+    def __getitem__(self, idx: int) -> Row: ...
+    @overload
+    def __getitem__(self, idx: slice) -> List[Row]: ...
+    def column_names(self) -> List[str]: ...
+    def schema(self) -> List[Tuple[str, DataType]]: ...
+    def column_values(self, name: str) -> List[Any]: ...
+    def column_values_by_idx(self, idx: int) -> List[Any]: ...
+    def column_as_array(self, name: str) -> ColumnArray: ...
+    def to_columns(self) -> Dict[str, List[Any]]: ...
+    def to_pandas(self) -> Any: ...
+    def to_arrow(self) -> Any: ...
+    def to_csv(self, path: Optional[str] = None, delimiter: str = ",",
+               header: bool = True,
+               timestamp_as_ms: bool = False) -> Optional[str]: ...
+    def to_bytes(self) -> bytes: ...
+    @classmethod
+    def from_bytes(cls, data: bytes) -> SqlQueryResponse: ...
+    @property
+    def affected_rows(self) -> Optional[int]: ...
+
+
+class ColumnArray:
+    @property
+    def name(self) -> str: ...
     @property
-    def affected_rows(self) -> int: ...
+    def dtype(self) -> str: ...
+    def __len__(self) -> int: ...
+    def __array__(self, dtype: Optional[Any] = None) -> Any: ...
 
 
 class DataType(enum.IntEnum):
@@ -50,37 +101,77 @@ class DataType(enum.IntEnum):
     Int8 = 13
     Boolean = 14
 
+    def __str__(self) -> str: ...
+    def is_numeric(self) -> bool: ...
+    def is_integer(self) -> bool: ...
+    def is_signed(self) -> bool: ...
+    def is_floating(self) -> bool: ...
+    def is_string_like(self) -> bool: ...
+
 
 class Column:
     def name(self) -> str: ...
     def value(self) -> Any: ...
+    def is_null(self) -> bool: ...
+    def as_datetime(self) -> datetime.datetime: ...
+    def timestamp_ms(self) -> int: ...
+    def timestamp_datetime(self) -> datetime.datetime: ...
+    def value_as_decimal(self) -> Optional[decimal.Decimal]: ...
+    def as_int(self) -> Optional[int]: ...
+    def as_float(self) -> Optional[float]: ...
+    def as_str(self) -> Optional[str]: ...
+    def as_bool(self) -> Optional[bool]: ...
+    def as_bytes(self) -> Optional[bytes]: ...
+    def as_uint8_bytes(self) -> Optional[bytes]: ...
     def data_type(self) -> DataType: ...
+    def __eq__(self, other: object) -> bool: ...
 
 
 class Row:
     def column(self, name: str) -> Optional[Column]: ...
-    def column_by_idx(self, idx: int) -> Optional[Column]: ...
+    def column_by_idx(self, idx: int) -> Column: ...
     def num_cols(self) -> int: ...
+    def __contains__(self, col_name: str) -> bool: ...
+    def get(self, col_name: str, default: Any = None) -> Any: ...
+    def keys(self) -> List[str]: ...
+    def items(self) -> List[Tuple[str, Any]]: ...
+    def to_dict(self) -> Dict[str, Any]: ...
     def iter_columns(self) -> ColumnIter: ...
+    def __eq__(self, other: object) -> bool: ...
 
 
 class ColumnIter:
-    def __iter__(self) -> Column: ...
+    def __iter__(self) -> ColumnIter: ...
+    def __next__(self) -> Column: ...
 
 
 class RowIter:
-    def __iter__(self) -> Row: ...
+    def __iter__(self) -> RowIter: ...
+    def __next__(self) -> Row: ...
+
+
+class DictIter:
+    def __iter__(self) -> DictIter: ...
+    def __next__(self) -> Dict[str, Any]: ...
 
 
 class Value:
-    pass
+    def data_type(self) -> DataType: ...
+    def __repr__(self) -> str: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
 
 
 class ValueBuilder:
     def __init__(self): ...
     def null(self) -> Value: ...
     def timestamp(self, val: int) -> Value: ...
-    def varbinary(self, val: bytes) -> Value: ...
+    def timestamp_from_datetime(
+        self, dt: Union[datetime.datetime, datetime.date],
+        assume_utc: bool = True) -> Value: ...
+    def varbinary(self, val: Union[bytes, bytearray, memoryview,
+                                   List[int]]) -> Value: ...
     def string(self, val: str) -> Value: ...
     def double(self, val: float) -> Value: ...
     def float(self, val: float) -> Value: ...
@@ -88,45 +179,161 @@ class ValueBuilder:
     def uint32(self, val: int) -> Value: ...
     def uint16(self, val: int) -> Value: ...
     def int64(self, val: int) -> Value: ...
+    def uint64_from_str(self, val: str) -> Value: ...
+    def int64_from_str(self, val: str) -> Value: ...
     def int32(self, val: int) -> Value: ...
     def int16(self, val: int) -> Value: ...
     def uint8(self, val: int) -> Value: ...
+    def int8(self, val: int) -> Value: ...
     def bool(self, val: bool) -> Value: ...
+    def from_object(self, obj: Any) -> Value: ...
+    def from_numpy(self, array: Any) -> List[Value]: ...
 
 
 class Point:
-    pass
+    @staticmethod
+    def from_dict(table: str, timestamp: int, tags: Dict[str, str],
+                  fields: Dict[str, Any]) -> Point: ...
+    def table(self) -> str: ...
+    def timestamp(self) -> int: ...
+    def tags(self) -> Dict[str, Any]: ...
+    def fields(self) -> Dict[str, Any]: ...
 
 
 class PointBuilder:
-    def __init__(self, table: str) -> PointBuilder: ...
-    def set_table(self, table: str): ...
-    def set_timestamp(self, timestamp_ms: int): ...
-    def set_tag(self, name: str, val: Value): ...
-    def set_field(self, name: str, val: Value): ...
+    def __init__(self, table: str, strict: bool = False): ...
+    def set_table(self, table: str) -> PointBuilder: ...
+    def set_timestamp(self, timestamp_ms: int) -> PointBuilder: ...
+    def set_timestamp_from_datetime(
+        self, dt: Union[datetime.datetime, datetime.date],
+        assume_utc: bool = True) -> PointBuilder: ...
+    def set_tag(self, name: str, val: Value) -> PointBuilder: ...
+    def set_field(self, name: str, val: Value) -> PointBuilder: ...
     def build(self) -> Point: ...
 
 
 class WriteRequest:
     def __init__(self): ...
     def add_point(self, point: Point): ...
-    def add_points(self, point: List[Point]): ...
+    def add_points(self, points: List[Point]): ...
+    def clear(self): ...
+    def dedup(self) -> int: ...
+    def split(self, max_points: int) -> List[WriteRequest]: ...
+    def split_by_bytes(self, max_bytes: int) -> List[WriteRequest]: ...
+    def merge(self, other: WriteRequest): ...
+    def num_points(self) -> int: ...
+    def is_empty(self) -> bool: ...
+    def __len__(self) -> int: ...
+    @staticmethod
+    def concat(reqs: List[WriteRequest]) -> WriteRequest: ...
+    @staticmethod
+    def from_dataframe(table: str, df: Any, timestamp_col: str,
+                       tag_cols: List[str],
+                       field_cols: List[str]) -> WriteRequest: ...
+    @staticmethod
+    def from_records(table: str, records: List[Dict[str, Any]],
+                     timestamp_key: str, tag_keys: List[str],
+                     field_keys: List[str],
+                     optional_keys: Optional[List[str]] = None) -> WriteRequest: ...
+    @staticmethod
+    def from_line_protocol(lines: str, precision: str = "ms") -> WriteRequest: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __getstate__(self) -> List[Tuple[str, int, List[Tuple[str, Tuple[int, Any]]], List[Tuple[str, Tuple[int, Any]]]]]: ...
+    def __setstate__(self, state: List[Tuple[str, int, List[Tuple[str, Tuple[int, Any]]], List[Tuple[str, Tuple[int, Any]]]]]): ...
 
 
 class WriteResponse:
     def get_success(self) -> int: ...
     def get_failed(self) -> int: ...
+    def total(self) -> int: ...
+    def __bool__(self) -> bool: ...
+    @property
+    def success(self) -> int: ...
+    @property
+    def failed(self) -> int: ...
+
+# errors
+
+
+class HoraeDBError(Exception):
+    code: Optional[int]
+    retriable: bool
+    detail: str
+
+
+class HoraeDBConnectionError(HoraeDBError): ...
+class HoraeDBTimeoutError(HoraeDBError): ...
+class QueryError(HoraeDBError): ...
+class WriteError(HoraeDBError): ...
+
+
+class TableNotFoundError(HoraeDBError):
+    table: Optional[str]
+
+# sql
+
+
+def escape_identifier(name: str) -> str: ...
+def escape_literal(value: Value) -> str: ...
 
 # client
 
 
-class Client:
-    def __init__(self, endpoint: str): ...
+def init_runtime(worker_threads: int): ...
 
-    async def write(self, ctx: RpcContext,
-                    req: WriteRequest) -> WriteResponse: ...
-    async def sql_query(self, ctx: RpcContext,
-                        req: SqlQueryRequest) -> SqlQueryResponse: ...
+
+class Client:
+    @classmethod
+    def from_url(cls, url: str) -> Client: ...
+    def close(self): ...
+    def use_database(self, db: Optional[str]): ...
+    def stats(self) -> Optional[Dict[str, Any]]: ...
+    def num_in_flight(self) -> int: ...
+    def __enter__(self) -> Client: ...
+    def __exit__(self, exc_type, exc_value, traceback) -> bool: ...
+    def __aenter__(self) -> Awaitable[Client]: ...
+    def __aexit__(self, exc_type, exc_value,
+                  traceback) -> Awaitable[bool]: ...
+
+    def write(self, ctx: Optional[RpcContext] = None,
+              req: WriteRequest = ...) -> Awaitable[WriteResponse]: ...
+    def write_points(
+        self, ctx: Optional[RpcContext] = None, table: Optional[str] = None,
+        points: List[Point] = ...) -> Awaitable[WriteResponse]: ...
+    def sql_query(
+        self, ctx: Optional[RpcContext] = None,
+        req: SqlQueryRequest = ...) -> Awaitable[SqlQueryResponse]: ...
+    def execute(self, sql: str, tables: Optional[List[str]] = None,
+                database: Optional[str] = None,
+                timeout_ms: Optional[int] = None) -> Awaitable[SqlQueryResponse]: ...
+    def dry_run(self, ctx: Optional[RpcContext] = None,
+                req: Union[WriteRequest, SqlQueryRequest] = ...) -> bytes: ...
+    def ping(self, ctx: Optional[RpcContext] = None,
+             timeout_ms: Optional[int] = None) -> Awaitable[bool]: ...
+    def ping_sync(self, ctx: Optional[RpcContext] = None,
+                  timeout_ms: Optional[int] = None) -> bool: ...
+    def write_sync(self, ctx: Optional[RpcContext] = None,
+                   req: WriteRequest = ...) -> WriteResponse: ...
+    def write_points_sync(
+        self, ctx: Optional[RpcContext] = None, table: Optional[str] = None,
+        points: List[Point] = ...) -> WriteResponse: ...
+    def sql_query_sync(self, ctx: Optional[RpcContext] = None,
+                       req: SqlQueryRequest = ...) -> SqlQueryResponse: ...
+    def execute_sync(self, sql: str, tables: Optional[List[str]] = None,
+                     database: Optional[str] = None,
+                     timeout_ms: Optional[int] = None) -> SqlQueryResponse: ...
+
+
+class BatchWriter:
+    def __init__(self, client: Client, max_points: int = 1000,
+                 flush_interval_ms: Optional[int] = None,
+                 ctx: Optional[RpcContext] = None): ...
+    def add_point(self, point: Point) -> Optional[WriteResponse]: ...
+    def num_pending(self) -> int: ...
+    def flush(self) -> Optional[WriteResponse]: ...
+    def close(self): ...
+    def __enter__(self) -> BatchWriter: ...
+    def __exit__(self, exc_type, exc_value, traceback) -> bool: ...
 
 
 class RpcConfig:
@@ -134,26 +341,67 @@ class RpcConfig:
     thread_num: int
     max_send_msg_len: int
     max_recv_msg_len: int
-    keepalive_time_ms: int
-    keepalive_timeout_ms: int
+    keep_alive_interval_ms: int
+    keep_alive_timeout_ms: int
+    keep_alive_while_idle: bool
     default_write_timeout_ms: int
     default_sql_query_timeout_ms: int
     connect_timeout_ms: int
 
 
-class RpcContext:
+class RetryConfig:
     def __init__(self): ...
-    timeout_ms: int
-    database: str
+    max_retries: int
+    initial_backoff_ms: int
+    max_backoff_ms: int
+    multiplier: float
+    retry_on_route_error: bool
+
+
+class RpcContext:
+    def __init__(self, database: Optional[str] = None,
+                 timeout_ms: Optional[int] = None,
+                 endpoint: Optional[str] = None): ...
+    def with_database(self, database: str) -> RpcContext: ...
+    def with_timeout_ms(self, timeout_ms: int) -> RpcContext: ...
+    def with_endpoint(self, endpoint: str) -> RpcContext: ...
+    timeout_ms: Optional[int]
+    database: Optional[str]
+    endpoint: Optional[str]
+
+
+class Mode(enum.IntEnum):
+    Direct = 0
+    Proxy = 1
+    @classmethod
+    def from_str(cls, s: str) -> Mode: ...
+    def __str__(self) -> str: ...
+
 
 class Authorization:
-    def __init__(self): ...
-    username: str
-    password: str
+    def __init__(self, username: str, password: str): ...
+
+
+class LoadBalance(enum.IntEnum):
+    RoundRobin = 0
+    Random = 1
+
 
 class Builder:
-    def __init__(self, endpoint: str): ...
+    def __init__(self, endpoint: str, mode: Mode): ...
+    def set_endpoints(
+        self, endpoints: List[str],
+        load_balance: LoadBalance = LoadBalance.RoundRobin): ...
     def set_rpc_config(self, conf: RpcConfig): ...
+    def set_default_timeout_ms(self, timeout_ms: int): ...
     def set_default_database(self, db: str): ...
     def set_authorization(self, auth: Authorization): ...
+    def set_retry_config(self, conf: RetryConfig): ...
+    def set_pool_size(self, pool_size: int): ...
+    def set_validate_requests(self, enabled: bool): ...
+    def set_verbose_errors(self, enabled: bool): ...
+    def enable_stats(self): ...
+    def set_max_concurrent_requests(self, max_requests: int): ...
+    def set_eager_connect(self, enabled: bool): ...
     def build(self) -> Client: ...
+    def build_and_connect(self) -> Awaitable[Client]: ...
@@ -0,0 +1,482 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The exceptions raised by the client, and the mapping from the errors of
+//! the rust client to them.
+
+use horaedb_client::Error as RustError;
+use pyo3::{create_exception, exceptions::PyException, prelude::*};
+use tonic::Code;
+
+create_exception!(
+    horaedb_client,
+    HoraeDBError,
+    PyException,
+    "The base class of the errors raised by the client."
+);
+create_exception!(
+    horaedb_client,
+    HoraeDBConnectionError,
+    HoraeDBError,
+    "The server can't be connected."
+);
+create_exception!(
+    horaedb_client,
+    HoraeDBTimeoutError,
+    HoraeDBError,
+    "The call doesn't finish before its deadline."
+);
+create_exception!(
+    horaedb_client,
+    QueryError,
+    HoraeDBError,
+    "The sql query fails, e.g. the sql is invalid."
+);
+create_exception!(
+    horaedb_client,
+    TableNotFoundError,
+    HoraeDBError,
+    "The table of the call doesn't exist."
+);
+create_exception!(
+    horaedb_client,
+    WriteError,
+    HoraeDBError,
+    "The write fails, and some of the points may have been written."
+);
+
+pub fn register_py_module(m: &PyModule) -> PyResult<()> {
+    let py = m.py();
+    // The defaults of the attributes set by `to_py_err`, for the exceptions
+    // not converted from the errors of the rust client.
+    let base_type = py.get_type::<HoraeDBError>();
+    base_type.setattr("code", py.None())?;
+    base_type.setattr("retriable", false)?;
+    base_type.setattr("detail", "")?;
+    py.get_type::<TableNotFoundError>()
+        .setattr("table", py.None())?;
+
+    m.add("HoraeDBError", base_type)?;
+    m.add(
+        "HoraeDBConnectionError",
+        py.get_type::<HoraeDBConnectionError>(),
+    )?;
+    m.add("HoraeDBTimeoutError", py.get_type::<HoraeDBTimeoutError>())?;
+    m.add("QueryError", py.get_type::<QueryError>())?;
+    m.add("WriteError", py.get_type::<WriteError>())?;
+    m.add("TableNotFoundError", py.get_type::<TableNotFoundError>())?;
+
+    Ok(())
+}
+
+/// The kind of the call in which an error occurs.
+#[derive(Clone, Copy, Debug)]
+pub enum CallKind {
+    Write,
+    SqlQuery,
+    Ping,
+}
+
+/// Convert the error of the rust client into the exception according to its
+/// cause, and the errors not caused by the connection or the deadline are
+/// attributed to the `kind` of the call.
+///
+/// The `TableNotFoundError` is raised for the server error of a missing table,
+/// including the ones of all the tables failed by a write in `Direct` mode,
+/// with the additional attribute `table` parsed from the message, or `None`
+/// if it can't be parsed.
+///
+/// The message of the exception is the concise description of the error, and
+/// it carries the attributes:
+/// - `code`: the code of the server error or the grpc status, or `None`.
+/// - `retriable`: whether the call may succeed if retried, in the same way as
+///   the retry of the client, and the route errors are taken as retriable
+///   only if `retry_on_route_error` is set.
+/// - `detail`: the raw message of the error, or the full debug chain of it if
+///   `verbose` is set.
+pub fn to_py_err(
+    err: RustError,
+    kind: CallKind,
+    retry_on_route_error: bool,
+    verbose: bool,
+) -> PyErr {
+    let msg = err.to_string();
+    let mut missing_table = None;
+    let py_err = if is_connection_error(&err) {
+        HoraeDBConnectionError::new_err(msg)
+    } else if is_timeout_error(&err) {
+        HoraeDBTimeoutError::new_err(msg)
+    } else if let Some(table) = table_not_found(&err) {
+        missing_table = Some(table);
+        TableNotFoundError::new_err(msg)
+    } else {
+        match kind {
+            CallKind::Write => WriteError::new_err(msg),
+            CallKind::SqlQuery => QueryError::new_err(msg),
+            CallKind::Ping => HoraeDBError::new_err(msg),
+        }
+    };
+
+    let (code, mut detail) = match &err {
+        RustError::Server(server_err) => (Some(server_err.code as i64), server_err.msg.clone()),
+        RustError::Rpc(status) => (Some(status.code() as i64), status.message().to_string()),
+        RustError::AuthFail(auth_err) => (Some(auth_err.code.clone() as i64), auth_err.msg.clone()),
+        err => (None, err.to_string()),
+    };
+    if verbose {
+        detail = format!("{err:?}");
+    }
+    Python::with_gil(|py| {
+        let value = py_err.value(py);
+        value
+            .setattr("code", code)
+            .and_then(|_| value.setattr("retriable", is_retriable(&err, retry_on_route_error)))
+            .and_then(|_| value.setattr("detail", detail))
+            .and_then(|_| match missing_table {
+                Some(table) => value.setattr("table", table),
+                None => Ok(()),
+            })
+            .err()
+            .unwrap_or(py_err)
+    })
+}
+
+/// Whether the call failed with the `err` may succeed if retried, and the
+/// route errors are taken as retriable if `retry_on_route_error` is set.
+pub fn is_retriable(err: &RustError, retry_on_route_error: bool) -> bool {
+    leaf_errors(err).into_iter().all(|(_, err)| match err {
+        RustError::Connect { .. } | RustError::Rpc(_) => is_connection_error(err),
+        RustError::Server(server_err) => {
+            retry_on_route_error && is_route_error(server_err.code, &server_err.msg)
+        }
+        _ => false,
+    })
+}
+
+/// The errors of the tables failed by a write in `Direct` mode, which routes
+/// the tables to the nodes and wraps their errors, or the `err` itself with no
+/// tables otherwise.
+fn leaf_errors(err: &RustError) -> Vec<(&[String], &RustError)> {
+    match err {
+        RustError::RouteBasedWriteError(write_err) if !write_err.errors.is_empty() => write_err
+            .errors
+            .iter()
+            .flat_map(|(tables, err)| match leaf_errors(err).as_slice() {
+                [(&[], err)] => vec![(tables.as_slice(), *err)],
+                errors => errors.to_vec(),
+            })
+            .collect(),
+        err => vec![(&[], err)],
+    }
+}
+
+/// The error of a write in `Direct` mode is a connection error only if the
+/// errors of all the failed tables are.
+pub fn is_connection_error(err: &RustError) -> bool {
+    leaf_errors(err).into_iter().all(|(_, err)| match err {
+        RustError::Connect { .. } => true,
+        RustError::Rpc(status) => status.code() == Code::Unavailable,
+        _ => false,
+    })
+}
+
+/// The deadline may be exceeded on the server, or on the client which cancels
+/// the call with the message of `tonic::transport::TimeoutExpired`.
+///
+/// The error of a write in `Direct` mode is a timeout error only if the errors
+/// of all the failed tables are.
+pub fn is_timeout_error(err: &RustError) -> bool {
+    leaf_errors(err).into_iter().all(|(_, err)| match err {
+        RustError::Rpc(status) => match status.code() {
+            Code::DeadlineExceeded => true,
+            Code::Cancelled => status.message() == "Timeout expired",
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+/// The server rejects the request with such error if the table is not on it,
+/// which means the cached route is outdated.
+pub fn is_route_error(code: u32, msg: &str) -> bool {
+    code == 400 && is_table_not_found(msg)
+}
+
+fn is_table_not_found(msg: &str) -> bool {
+    msg.contains("Table") && msg.contains("not found")
+}
+
+/// Check whether the `err` is about a missing table, and the table name is
+/// parsed from the `table:{name}` in the message if any.
+///
+/// The error of a write in `Direct` mode is about a missing table only if the
+/// errors of all the failed tables are, and the table name is taken from the
+/// failed tables if it can't be parsed.
+fn table_not_found(err: &RustError) -> Option<Option<String>> {
+    let mut table = None;
+    for (tables, err) in leaf_errors(err) {
+        let parsed = match err {
+            RustError::Server(server_err) => missing_table(&server_err.msg)?,
+            _ => return None,
+        };
+        table = table.or(parsed).or_else(|| match tables {
+            [table] => Some(table.clone()),
+            _ => None,
+        });
+    }
+    Some(table)
+}
+
+/// Check whether the server error `msg` is about a missing table, and parse
+/// the table name from the `table:{name}` in it if any.
+fn missing_table(msg: &str) -> Option<Option<String>> {
+    if !is_table_not_found(msg) {
+        return None;
+    }
+    let table = msg.split_once("table:").map(|(_, rest)| {
+        rest.split(|c: char| c == ',' || c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    });
+    Some(table.filter(|table| !table.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use horaedb_client::model::write::Response as RustWriteResponse;
+    use tonic::Status;
+
+    use super::*;
+
+    /// The error of a write in `Direct` mode, whose tables fail with the
+    /// `errors`.
+    fn route_based_write_error(errors: Vec<(&str, RustError)>) -> RustError {
+        let results: Vec<(Vec<String>, Result<RustWriteResponse, RustError>)> = errors
+            .into_iter()
+            .map(|(table, err)| (vec![table.to_string()], Err(err)))
+            .collect();
+        RustError::RouteBasedWriteError(results.into())
+    }
+
+    #[test]
+    fn test_missing_table() {
+        assert_eq!(
+            missing_table("Table not found, table:cpu, catalog:horaedb"),
+            Some(Some("cpu".to_string()))
+        );
+        assert_eq!(
+            missing_table("Table not found, table:cpu"),
+            Some(Some("cpu".to_string()))
+        );
+        assert_eq!(missing_table("Table not found, table:"), Some(None));
+        assert_eq!(missing_table("Table not found"), Some(None));
+        assert_eq!(missing_table("invalid sql"), None);
+    }
+
+    #[test]
+    fn test_leaf_errors() {
+        let err = RustError::NoDatabase;
+        let leaves = leaf_errors(&err);
+        assert_eq!(leaves.len(), 1);
+        assert!(leaves[0].0.is_empty());
+
+        let err = route_based_write_error(vec![
+            ("cpu", RustError::NoDatabase),
+            ("mem", RustError::Client("failed".to_string())),
+        ]);
+        let tables: Vec<_> = leaf_errors(&err)
+            .into_iter()
+            .map(|(tables, _)| tables.to_vec())
+            .collect();
+        assert_eq!(
+            tables,
+            vec![vec!["cpu".to_string()], vec!["mem".to_string()]]
+        );
+
+        // The error without the failed tables is taken as is.
+        let err = route_based_write_error(vec![]);
+        let leaves = leaf_errors(&err);
+        assert_eq!(leaves.len(), 1);
+        assert!(matches!(leaves[0].1, RustError::RouteBasedWriteError(_)));
+    }
+
+    fn connect_error() -> RustError {
+        RustError::Connect {
+            addr: "127.0.0.1:8831".to_string(),
+            source: Box::new(RustError::Unknown("connection refused".to_string())),
+        }
+    }
+
+    fn timeout_error() -> RustError {
+        RustError::Rpc(Status::cancelled("Timeout expired"))
+    }
+
+    #[test]
+    fn test_connection_error() {
+        assert!(is_connection_error(&connect_error()));
+        assert!(is_connection_error(&RustError::Rpc(Status::unavailable(
+            "node down"
+        ))));
+        assert!(!is_connection_error(&RustError::Rpc(Status::internal(
+            "node down"
+        ))));
+        assert!(!is_connection_error(&timeout_error()));
+        assert!(!is_connection_error(&RustError::NoDatabase));
+    }
+
+    #[test]
+    fn test_connection_error_in_route_based_write() {
+        let err = route_based_write_error(vec![("cpu", connect_error())]);
+        assert!(is_connection_error(&err));
+        assert!(is_retriable(&err, false));
+
+        let err = route_based_write_error(vec![
+            ("cpu", connect_error()),
+            ("mem", RustError::Rpc(Status::unavailable("node down"))),
+        ]);
+        assert!(is_connection_error(&err));
+
+        let err = route_based_write_error(vec![("cpu", connect_error()), ("mem", timeout_error())]);
+        assert!(!is_connection_error(&err));
+        assert!(!is_retriable(&err, false));
+    }
+
+    #[test]
+    fn test_timeout_error() {
+        assert!(is_timeout_error(&timeout_error()));
+        assert!(is_timeout_error(&RustError::Rpc(
+            Status::deadline_exceeded("deadline")
+        )));
+        assert!(!is_timeout_error(&RustError::Rpc(Status::cancelled(
+            "cancelled by user"
+        ))));
+        assert!(!is_timeout_error(&connect_error()));
+    }
+
+    #[test]
+    fn test_timeout_error_in_route_based_write() {
+        let err = route_based_write_error(vec![("cpu", timeout_error())]);
+        assert!(is_timeout_error(&err));
+
+        let err = route_based_write_error(vec![
+            ("cpu", timeout_error()),
+            ("mem", RustError::Rpc(Status::internal("failed"))),
+        ]);
+        assert!(!is_timeout_error(&err));
+    }
+
+    #[test]
+    fn test_to_py_err() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let to_write_err = |err| to_py_err(err, CallKind::Write, false, false);
+
+            let err = to_write_err(connect_error());
+            assert!(err.is_instance_of::<HoraeDBConnectionError>(py));
+            assert!(err
+                .value(py)
+                .getattr("retriable")
+                .unwrap()
+                .is_true()
+                .unwrap());
+
+            let err = to_write_err(route_based_write_error(vec![("cpu", timeout_error())]));
+            assert!(err.is_instance_of::<HoraeDBTimeoutError>(py));
+
+            let err = to_write_err(route_based_write_error(vec![("cpu", connect_error())]));
+            assert!(err.is_instance_of::<HoraeDBConnectionError>(py));
+
+            let err = to_write_err(RustError::Rpc(Status::internal("failed")));
+            assert!(err.is_instance_of::<WriteError>(py));
+            assert!(err.is_instance_of::<HoraeDBError>(py));
+            assert!(!err.is_instance_of::<HoraeDBTimeoutError>(py));
+
+            let err = to_py_err(
+                RustError::Client("invalid sql".to_string()),
+                CallKind::SqlQuery,
+                false,
+                false,
+            );
+            assert!(err.is_instance_of::<QueryError>(py));
+        });
+    }
+
+    #[test]
+    fn test_to_py_err_detail() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let attrs = |err: RustError, verbose: bool| {
+                let err = to_py_err(err, CallKind::Write, false, verbose);
+                let value = err.value(py);
+                let attr = |name: &str| -> Option<String> {
+                    value.getattr(name).unwrap().extract().unwrap()
+                };
+                (
+                    value.str().unwrap().to_string(),
+                    attr("detail"),
+                    value
+                        .getattr("code")
+                        .unwrap()
+                        .extract::<Option<i64>>()
+                        .unwrap(),
+                )
+            };
+
+            // The detail is the raw message of the server in concise mode.
+            let rpc_err = || RustError::Rpc(Status::internal("disk full"));
+            let (msg, detail, code) = attrs(rpc_err(), false);
+            assert_eq!(msg, rpc_err().to_string());
+            assert!(msg.contains("disk full"), "msg:{msg}");
+            assert_eq!(detail.as_deref(), Some("disk full"));
+            assert_eq!(code, Some(tonic::Code::Internal as i64));
+
+            // The detail is the debug format of the error in verbose mode, while
+            // the message is the same.
+            let (verbose_msg, verbose_detail, verbose_code) = attrs(rpc_err(), true);
+            assert_eq!(verbose_msg, msg);
+            assert_eq!(verbose_detail, Some(format!("{:?}", rpc_err())));
+            assert_ne!(verbose_detail, detail);
+            assert_eq!(verbose_code, code);
+
+            let client_err = || RustError::Client("invalid sql".to_string());
+            let (msg, detail, code) = attrs(client_err(), false);
+            assert_eq!(msg, client_err().to_string());
+            assert_eq!(detail, Some(client_err().to_string()));
+            assert_eq!(code, None);
+            let (_, detail, _) = attrs(client_err(), true);
+            assert_eq!(detail, Some(format!("{:?}", client_err())));
+
+            // The errors wrapped by the route based write are all kept in the
+            // verbose detail.
+            let err = || route_based_write_error(vec![("cpu", connect_error())]);
+            let (msg, detail, _) = attrs(err(), true);
+            assert_eq!(msg, err().to_string());
+            let detail = detail.unwrap();
+            assert!(detail.contains("RouteBasedWriteError"), "detail:{detail}");
+            assert!(detail.contains("cpu"), "detail:{detail}");
+        });
+    }
+
+    #[test]
+    fn test_table_not_found_not_server_error() {
+        assert_eq!(table_not_found(&RustError::NoDatabase), None);
+
+        let err = route_based_write_error(vec![("cpu", RustError::Rpc(Status::internal("")))]);
+        assert_eq!(table_not_found(&err), None);
+    }
+}
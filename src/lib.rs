@@ -15,12 +15,22 @@
 // specific language governing permissions and limitations
 // under the License.
 
+// The `#[new]` expansion of pyo3 0.16 defines an impl in a function, which is
+// linted on newer toolchains.
+#[allow(non_local_definitions)]
 mod client;
+// The `create_exception!` expansion of pyo3 checks a cfg unknown to newer
+// toolchains.
+#[allow(unexpected_cfgs)]
+mod errors;
+mod line_protocol;
 // Due to clippy's bug, see https://github.com/rust-lang/rust-clippy/issues/8971.
-#[allow(clippy::borrow_deref_ref)]
+// And the same as `client` for `non_local_definitions`.
+#[allow(clippy::borrow_deref_ref, non_local_definitions)]
 mod model;
+mod sql;
 
-use pyo3::prelude::*;
+use pyo3::{prelude::*, types::PyDict};
 
 #[pymodule]
 fn horaedb_client(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -29,7 +39,28 @@ fn horaedb_client(_py: Python, m: &PyModule) -> PyResult<()> {
     }
 
     client::register_py_module(m)?;
+    errors::register_py_module(m)?;
     model::register_py_module(m)?;
+    sql::register_py_module(m)?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_function(wrap_pyfunction!(features, m)?)?;
 
     Ok(())
 }
+
+/// The optional features compiled in, from the feature name to whether it is
+/// available.
+///
+/// The conversions to pyarrow, pandas and numpy are always compiled in, and
+/// still require the python packages at runtime. The tls and the compression
+/// are not supported by the rust client yet.
+#[pyfunction]
+fn features(py: Python<'_>) -> PyResult<&PyDict> {
+    let features = PyDict::new(py);
+    features.set_item("arrow", true)?;
+    features.set_item("pandas", true)?;
+    features.set_item("numpy", true)?;
+    features.set_item("tls", false)?;
+    features.set_item("compression", false)?;
+    Ok(features)
+}
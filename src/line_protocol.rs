@@ -0,0 +1,340 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The parser of the InfluxDB line protocol:
+//! `measurement[,tag=value...] field=value[,field=value...] [timestamp]`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use horaedb_client::model::{
+    value::{TimestampMs, Value as RustValue},
+    write::point::{Point as RustPoint, PointBuilder as RustPointBuilder},
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Parse the `lines` into points, one point per line, and the empty lines and
+/// the comment lines starting with `#` are skipped.
+///
+/// The measurement is taken as the table and the tag values as strings. The
+/// timestamps are in the unit of the `precision` (`ns`, `us`, `ms` or `s`),
+/// and the points without timestamp are given the current time.
+pub fn parse_lines(lines: &str, precision: &str) -> PyResult<Vec<RustPoint>> {
+    let unit_ms = Precision::parse(precision)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as TimestampMs)
+        .unwrap_or_default();
+
+    let mut points = Vec::new();
+    for (idx, line) in lines.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let point = parse_line(line, unit_ms, now).map_err(|e| {
+            PyValueError::new_err(format!("invalid line protocol, line:{}, err:{e}", idx + 1))
+        })?;
+        points.push(point);
+    }
+
+    Ok(points)
+}
+
+#[derive(Clone, Copy)]
+enum Precision {
+    Nanosecond,
+    Microsecond,
+    Millisecond,
+    Second,
+}
+
+impl Precision {
+    fn parse(precision: &str) -> PyResult<Self> {
+        match precision {
+            "ns" => Ok(Precision::Nanosecond),
+            "us" => Ok(Precision::Microsecond),
+            "ms" => Ok(Precision::Millisecond),
+            "s" => Ok(Precision::Second),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown precision, precision:{precision}, options:ns, us, ms, s"
+            ))),
+        }
+    }
+
+    fn to_ms(self, timestamp: i64) -> Option<TimestampMs> {
+        match self {
+            Precision::Nanosecond => Some(timestamp / 1_000_000),
+            Precision::Microsecond => Some(timestamp / 1_000),
+            Precision::Millisecond => Some(timestamp),
+            Precision::Second => timestamp.checked_mul(1_000),
+        }
+    }
+}
+
+fn parse_line(line: &str, precision: Precision, now: TimestampMs) -> Result<RustPoint, String> {
+    // The measurement and the tags are never quoted, so only the fields are
+    // split with the quotes respected.
+    let (series, rest) = match split_unescaped(line, ' ', false).as_slice() {
+        [series, rest @ ..] if !rest.is_empty() => (*series, &line[series.len() + 1..]),
+        _ => return Err("no fields".to_string()),
+    };
+    let (fields, timestamp) = match split_unescaped(rest, ' ', true).as_slice() {
+        [fields] => (*fields, None),
+        [fields, timestamp] => (*fields, Some(*timestamp)),
+        _ => return Err("unexpected content after timestamp".to_string()),
+    };
+
+    let mut series_parts = split_unescaped(series, ',', false).into_iter();
+    let measurement = unescape(series_parts.next().unwrap_or_default());
+    if measurement.is_empty() {
+        return Err("measurement is empty".to_string());
+    }
+
+    let timestamp = match timestamp {
+        Some(timestamp) => {
+            let timestamp: i64 = timestamp
+                .parse()
+                .map_err(|_| format!("invalid timestamp, timestamp:{timestamp}"))?;
+            precision
+                .to_ms(timestamp)
+                .ok_or_else(|| format!("timestamp out of range, timestamp:{timestamp}"))?
+        }
+        None => now,
+    };
+
+    let mut builder = RustPointBuilder::new(measurement).timestamp(timestamp);
+    for tag in series_parts {
+        let (name, value) = split_key_value(tag, false)?;
+        builder = builder.tag(name, RustValue::String(unescape(value)));
+    }
+    for field in split_unescaped(fields, ',', true) {
+        let (name, value) = split_key_value(field, true)?;
+        builder = builder.field(name, parse_field_value(value)?);
+    }
+
+    builder.build()
+}
+
+/// Split the `s` by the `sep` chars which are neither escaped by backslash nor
+/// inside the double quotes if `quoted` is set.
+fn split_unescaped(s: &str, sep: char, quoted: bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    let mut in_quotes = false;
+    for (idx, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if quoted && c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == sep && !in_quotes {
+            parts.push(&s[start..idx]);
+            start = idx + c.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn split_key_value(s: &str, quoted: bool) -> Result<(String, &str), String> {
+    match split_unescaped(s, '=', quoted).as_slice() {
+        [key, _, ..] if !key.is_empty() => Ok((unescape(key), &s[key.len() + 1..])),
+        _ => Err(format!("invalid key value pair, pair:{s}")),
+    }
+}
+
+/// Remove the backslashes escaping the special chars of the keys.
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(',' | '=' | ' ' | '\\')) => unescaped.push(chars.next().unwrap()),
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Parse the field value, which is a string if double quoted, an integer with
+/// the `i` suffix, an unsigned integer with the `u` suffix, a boolean or a
+/// float otherwise.
+fn parse_field_value(s: &str) -> Result<RustValue, String> {
+    let invalid = || format!("invalid field value, value:{s}");
+
+    if let Some(quoted) = s.strip_prefix('"') {
+        let quoted = quoted.strip_suffix('"').ok_or_else(invalid)?;
+        let mut unescaped = String::with_capacity(quoted.len());
+        let mut chars = quoted.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('\\', Some('"' | '\\')) => unescaped.push(chars.next().unwrap()),
+                _ => unescaped.push(c),
+            }
+        }
+        return Ok(RustValue::String(unescaped));
+    }
+
+    match s {
+        "t" | "T" | "true" | "True" | "TRUE" => return Ok(RustValue::Boolean(true)),
+        "f" | "F" | "false" | "False" | "FALSE" => return Ok(RustValue::Boolean(false)),
+        _ => {}
+    }
+    if let Some(v) = s.strip_suffix('i') {
+        return v.parse().map(RustValue::Int64).map_err(|_| invalid());
+    }
+    if let Some(v) = s.strip_suffix('u') {
+        return v.parse().map(RustValue::UInt64).map_err(|_| invalid());
+    }
+    match s.parse::<f64>() {
+        Ok(v) if v.is_finite() => Ok(RustValue::Double(v)),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: TimestampMs = 1_700_000_000_000;
+
+    fn parse(line: &str) -> Result<RustPoint, String> {
+        parse_line(line, Precision::Millisecond, NOW)
+    }
+
+    fn string(s: &str) -> RustValue {
+        RustValue::String(s.to_string())
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let point = parse("cpu,host=a,region=b usage=0.5,count=3i,total=7u,up=t 1000").unwrap();
+        assert_eq!(point.table, "cpu");
+        assert_eq!(point.timestamp, 1000);
+        assert_eq!(point.tags["host"], string("a"));
+        assert_eq!(point.tags["region"], string("b"));
+        assert_eq!(point.fields["usage"], RustValue::Double(0.5));
+        assert_eq!(point.fields["count"], RustValue::Int64(3));
+        assert_eq!(point.fields["total"], RustValue::UInt64(7));
+        assert_eq!(point.fields["up"], RustValue::Boolean(true));
+    }
+
+    #[test]
+    fn test_parse_line_escapes() {
+        let point =
+            parse(r"my\ cpu,host\,name=a\ b\=c,k=v\,w field\ one\=x=1i,f\,2=FALSE 1").unwrap();
+        assert_eq!(point.table, "my cpu");
+        assert_eq!(point.tags["host,name"], string("a b=c"));
+        assert_eq!(point.tags["k"], string("v,w"));
+        assert_eq!(point.fields["field one=x"], RustValue::Int64(1));
+        assert_eq!(point.fields["f,2"], RustValue::Boolean(false));
+    }
+
+    #[test]
+    fn test_parse_line_quoted_string() {
+        let point = parse(r#"log msg="a b, c=d \"quoted\" \\",n=-1i 1"#).unwrap();
+        assert_eq!(point.fields["msg"], string(r#"a b, c=d "quoted" \"#));
+        assert_eq!(point.fields["n"], RustValue::Int64(-1));
+
+        let point = parse(r#"log msg="" 1"#).unwrap();
+        assert_eq!(point.fields["msg"], string(""));
+    }
+
+    #[test]
+    fn test_parse_line_integer_suffix() {
+        let point = parse("t a=9223372036854775807i,b=-9223372036854775808i,c=1 1").unwrap();
+        assert_eq!(point.fields["a"], RustValue::Int64(i64::MAX));
+        assert_eq!(point.fields["b"], RustValue::Int64(i64::MIN));
+        assert_eq!(point.fields["c"], RustValue::Double(1.0));
+
+        assert!(parse("t a=9223372036854775808i 1").is_err());
+        assert!(parse("t a=-1u 1").is_err());
+        assert!(parse("t a=1.5i 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_line_precision() {
+        let parse_ts = |precision: &str, timestamp: &str| {
+            let precision = Precision::parse(precision).unwrap();
+            parse_line(&format!("t v=1 {timestamp}"), precision, NOW)
+                .unwrap()
+                .timestamp
+        };
+        assert_eq!(parse_ts("ns", "1700000000123456789"), 1_700_000_000_123);
+        assert_eq!(parse_ts("us", "1700000000123456"), 1_700_000_000_123);
+        assert_eq!(parse_ts("ms", "1700000000123"), 1_700_000_000_123);
+        assert_eq!(parse_ts("s", "1700000000"), 1_700_000_000_000);
+        assert_eq!(parse_ts("s", "-1"), -1000);
+
+        assert!(Precision::parse("m").is_err());
+        assert!(parse_line("t v=1 9223372036854775807", Precision::Second, NOW).is_err());
+    }
+
+    #[test]
+    fn test_parse_line_missing_timestamp() {
+        let point = parse("cpu,host=a usage=1").unwrap();
+        assert_eq!(point.timestamp, NOW);
+    }
+
+    #[test]
+    fn test_parse_malformed_lines() {
+        let malformed = [
+            "cpu",
+            "cpu,host=a",
+            "cpu ",
+            ",host=a v=1",
+            "cpu,host v=1",
+            "cpu,=a v=1",
+            "cpu v",
+            "cpu =1",
+            "cpu v=",
+            "cpu v=abc",
+            "cpu v=inf",
+            "cpu v=NaN",
+            "cpu v=\"unterminated",
+            "cpu v=\"a\"b",
+            "cpu v=1,",
+            "cpu v=1 abc",
+            "cpu v=1 1.5",
+            "cpu v=1 1 2",
+            "cpu\\ v=1",
+            "cpu,host=a\\ v=1",
+            "\\",
+            "=",
+            "\"",
+            "cpu v=1i\\",
+            "ä,ö=ü ß=\"€",
+        ];
+        for line in malformed {
+            assert!(parse(line).is_err(), "line:{line}");
+        }
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let lines = "\n# comment\ncpu v=1 1\n  \ncpu v=2 2\n";
+        let points = parse_lines(lines, "ms").unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].timestamp, 2);
+
+        assert!(parse_lines("cpu v=1 1\ncpu v 2", "ms").is_err());
+        assert!(parse_lines("cpu v=1 1", "h").is_err());
+        assert!(parse_lines("", "ms").unwrap().is_empty());
+    }
+}
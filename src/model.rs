@@ -17,11 +17,36 @@
 
 //! Read/Write request and response, and useful tools for them.
 
-use std::sync::Arc;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    num::{IntErrorKind, ParseIntError},
+    os::raw::c_long,
+    ptr::addr_of,
+    str::FromStr,
+    sync::Arc,
+};
 
+use arrow::{
+    array::{
+        Array, ArrayRef, BinaryArray, BinaryBuilder, BooleanArray, BooleanBuilder, Float32Array,
+        Float32Builder, Float64Array, Float64Builder, Int16Array, Int16Builder, Int32Array,
+        Int32Builder, Int64Array, Int64Builder, Int8Array, Int8Builder, NullArray, StringArray,
+        StringBuilder, StructArray, TimestampMillisecondArray, TimestampMillisecondBuilder,
+        UInt16Array, UInt16Builder, UInt32Array, UInt32Builder, UInt64Array, UInt64Builder,
+        UInt8Array, UInt8Builder,
+    },
+    datatypes::{DataType as ArrowDataType, Field, Schema, TimeUnit},
+    error::ArrowError,
+    ffi::{FFI_ArrowArray, FFI_ArrowSchema},
+    ipc::{reader::StreamReader, writer::StreamWriter},
+    record_batch::{RecordBatch, RecordBatchOptions},
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::DateTime;
 use horaedb_client::model::{
     sql_query::{
-        row::{Column as RustColumn, Row as RustRow},
+        row::{Column as RustColumn, Row as RustRow, RowBuilder as RustRowBuilder},
         Request as RustSqlQueryRequest, Response as RustSqlQueryResponse,
     },
     value::{DataType as RustDataType, TimestampMs, Value as RustValue},
@@ -30,16 +55,28 @@ use horaedb_client::model::{
         Request as RustWriteRequest, Response as RustWriteResponse,
     },
 };
-use pyo3::{exceptions::PyTypeError, prelude::*};
+use pyo3::{
+    exceptions::{
+        PyException, PyImportError, PyIndexError, PyKeyError, PyOverflowError, PyTypeError,
+        PyValueError,
+    },
+    prelude::*,
+    pyclass::CompareOp,
+    types::{PyBool, PyByteArray, PyBytes, PyDict, PyFloat, PyLong, PySlice, PyString, PyType},
+};
+
+use crate::{line_protocol, sql};
 
 pub fn register_py_module(m: &PyModule) -> PyResult<()> {
     m.add_class::<SqlQueryRequest>()?;
     m.add_class::<SqlQueryResponse>()?;
     m.add_class::<DataType>()?;
     m.add_class::<Column>()?;
+    m.add_class::<ColumnArray>()?;
     m.add_class::<ColumnIter>()?;
     m.add_class::<Row>()?;
     m.add_class::<RowIter>()?;
+    m.add_class::<DictIter>()?;
     m.add_class::<Value>()?;
     m.add_class::<ValueBuilder>()?;
     m.add_class::<PointBuilder>()?;
@@ -51,7 +88,7 @@ pub fn register_py_module(m: &PyModule) -> PyResult<()> {
 }
 
 /// A sql query request.
-#[pyclass]
+#[pyclass(module = "horaedb_client")]
 #[derive(Clone, Debug)]
 pub struct SqlQueryRequest {
     rust_req: RustSqlQueryRequest,
@@ -65,9 +102,65 @@ impl SqlQueryRequest {
         Self { rust_req }
     }
 
+    #[getter]
+    pub fn tables(&self) -> Vec<String> {
+        self.rust_req.tables.clone()
+    }
+
+    #[getter]
+    pub fn sql(&self) -> &str {
+        &self.rust_req.sql
+    }
+
+    /// Build a request from the `sql_template` with the `?` placeholders
+    /// replaced by the literals of the `params` in order.
+    ///
+    /// The numbers are inlined, the strings are quoted and escaped, and the
+    /// timestamps are inlined as milliseconds.
+    #[staticmethod]
+    pub fn with_params(
+        tables: Vec<String>,
+        sql_template: &str,
+        params: Vec<Value>,
+    ) -> PyResult<SqlQueryRequest> {
+        let params: Vec<_> = params.into_iter().map(|v| v.raw_val).collect();
+        let sql = sql::substitute_params(sql_template, &params)?;
+        Ok(Self::new(tables, sql))
+    }
+
+    /// Return a copy of the request fetching one page of the results, with
+    /// the `LIMIT` and `OFFSET` clauses appended to the sql.
+    ///
+    /// A `ValueError` is raised if the `limit` is zero, or the sql has either
+    /// clause already.
+    #[args(offset = "0")]
+    pub fn paginate(&self, limit: u64, offset: u64) -> PyResult<SqlQueryRequest> {
+        let sql = sql::paginate(&self.rust_req.sql, limit, offset)?;
+        Ok(Self::new(self.rust_req.tables.clone(), sql))
+    }
+
     pub fn __str__(&self) -> String {
         format!("{self:?}")
     }
+
+    pub fn __richcmp__(&self, py: Python<'_>, other: &PyAny, op: CompareOp) -> PyObject {
+        let other = match other.extract::<PyRef<SqlQueryRequest>>() {
+            Ok(other) => other,
+            Err(_) => return py.NotImplemented(),
+        };
+        let eq = self.rust_req.tables == other.rust_req.tables
+            && self.rust_req.sql == other.rust_req.sql;
+        match op {
+            CompareOp::Eq => eq.into_py(py),
+            CompareOp::Ne => (!eq).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    pub fn __reduce__(&self, py: Python<'_>) -> (PyObject, (Vec<String>, String)) {
+        let args = (self.rust_req.tables.clone(), self.rust_req.sql.clone());
+        (py.get_type::<Self>().into(), args)
+    }
 }
 
 impl From<SqlQueryRequest> for RustSqlQueryRequest {
@@ -87,16 +180,84 @@ impl AsRef<RustSqlQueryRequest> for SqlQueryRequest {
 #[derive(Clone, Debug)]
 pub struct SqlQueryResponse {
     rust_rows: Arc<Vec<RustRow>>,
-    #[pyo3(get)]
     affected_rows: u32,
 }
 
+impl SqlQueryResponse {
+    fn make_row(&self, row_idx: usize) -> Row {
+        Row {
+            rust_rows: self.rust_rows.clone(),
+            row_idx,
+        }
+    }
+
+    /// Build a record batch of the rows, with the `metadata` in its schema.
+    fn make_record_batch(&self, metadata: HashMap<String, String>) -> PyResult<RecordBatch> {
+        let mut fields = Vec::new();
+        let mut arrays = Vec::new();
+        if let Some(first_row) = self.rust_rows.first() {
+            for (col_idx, col) in first_row.columns().iter().enumerate() {
+                let col_values = self
+                    .rust_rows
+                    .iter()
+                    .map(|row| row.columns()[col_idx].value());
+                let (field, array) = make_arrow_column(col.name(), col_values)?;
+                fields.push(field);
+                arrays.push(array);
+            }
+        }
+        let schema = Schema::new_with_metadata(fields, metadata);
+        let options = RecordBatchOptions::new().with_row_count(Some(self.rust_rows.len()));
+        RecordBatch::try_new_with_options(Arc::new(schema), arrays, &options)
+            .map_err(|e| PyException::new_err(format!("failed to build record batch, err:{e}")))
+    }
+
+    /// Find the index of the column `name` in the first row, and `KeyError` is
+    /// raised if it is not found.
+    fn column_idx(&self, name: &str) -> PyResult<usize> {
+        self.rust_rows
+            .first()
+            .and_then(|row| row.columns().iter().position(|c| c.name() == name))
+            .ok_or_else(|| PyKeyError::new_err(format!("column not found, column:{name}")))
+    }
+
+    /// The `col_idx` should be ensured in range.
+    fn collect_column_values(&self, py: Python<'_>, col_idx: usize) -> Vec<PyObject> {
+        self.rust_rows
+            .iter()
+            .map(|row| value_to_object(py, row.columns()[col_idx].value()))
+            .collect()
+    }
+}
+
 #[pymethods]
 impl SqlQueryResponse {
     pub fn num_rows(&self) -> usize {
         self.rust_rows.len()
     }
 
+    /// Whether the response is of a query returning rows.
+    ///
+    /// The server responds either the rows or the number of the affected rows,
+    /// and the rust client reports no rows and zero affected rows for both a
+    /// query returning nothing and a mutation affecting nothing, which are
+    /// taken as a query here.
+    pub fn is_query(&self) -> bool {
+        !self.is_mutation()
+    }
+
+    /// Whether the response is of a mutation, e.g. `INSERT`, reporting the
+    /// number of the affected rows.
+    pub fn is_mutation(&self) -> bool {
+        self.rust_rows.is_empty() && self.affected_rows > 0
+    }
+
+    /// The number of the rows affected by a mutation, and `None` for a query.
+    #[getter]
+    pub fn affected_rows(&self) -> Option<u32> {
+        self.is_mutation().then_some(self.affected_rows)
+    }
+
     pub fn row_by_idx(&self, row_idx: usize) -> Option<Row> {
         if self.rust_rows.len() > row_idx {
             Some(Row {
@@ -115,11 +276,607 @@ impl SqlQueryResponse {
         }
     }
 
+    /// The same as [`SqlQueryResponse::iter_rows`].
+    pub fn __iter__(&self) -> RowIter {
+        self.iter_rows()
+    }
+
+    /// Iterate the rows as dicts, like [`Row::to_dict`].
+    pub fn iter_dicts(&self, py: Python<'_>) -> DictIter {
+        let col_names = self
+            .rust_rows
+            .first()
+            .map(|row| {
+                row.columns()
+                    .iter()
+                    .map(|c| PyString::new(py, c.name()).into())
+                    .collect()
+            })
+            .unwrap_or_default();
+        DictIter {
+            rust_rows: self.rust_rows.clone(),
+            next_row_idx: 0,
+            col_names,
+        }
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.rust_rows.len()
+    }
+
+    /// A response is true if it has rows or affected any rows.
+    pub fn __bool__(&self) -> bool {
+        !self.rust_rows.is_empty() || self.affected_rows > 0
+    }
+
+    /// Get a [Row] by an index or a list of [Row]s by a slice.
+    ///
+    /// Negative indexes count from the end, like a python sequence.
+    pub fn __getitem__(&self, py: Python<'_>, key: &PyAny) -> PyResult<PyObject> {
+        if let Ok(slice) = key.downcast::<PySlice>() {
+            let indices = slice.indices(self.rust_rows.len() as c_long)?;
+            let rows = (0..indices.slicelength)
+                .map(|i| self.make_row((indices.start + i * indices.step) as usize))
+                .collect::<Vec<_>>();
+            return Ok(rows.into_py(py));
+        }
+
+        let idx: isize = key.extract()?;
+        let num_rows = self.rust_rows.len() as isize;
+        let row_idx = if idx < 0 { idx + num_rows } else { idx };
+        if row_idx < 0 || row_idx >= num_rows {
+            return Err(PyIndexError::new_err(format!(
+                "row index out of range, index:{idx}, num_rows:{num_rows}"
+            )));
+        }
+
+        Ok(self.make_row(row_idx as usize).into_py(py))
+    }
+
+    /// The names of the columns in the result, which is empty if no rows are
+    /// returned.
+    pub fn column_names(&self) -> Vec<String> {
+        self.rust_rows
+            .first()
+            .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// The names and data types of the columns in the result, which is empty
+    /// if no rows are returned.
+    pub fn schema(&self) -> Vec<(String, DataType)> {
+        self.rust_rows
+            .first()
+            .map(|row| {
+                row.columns()
+                    .iter()
+                    .map(|c| (c.name().to_string(), c.value().data_type().into()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the values of the column named `name` in all the rows.
+    ///
+    /// The column is looked up in the first row, and `KeyError` is raised if
+    /// it is not found.
+    pub fn column_values(&self, py: Python<'_>, name: &str) -> PyResult<Vec<PyObject>> {
+        let col_idx = self.column_idx(name)?;
+        Ok(self.collect_column_values(py, col_idx))
+    }
+
+    /// Get the values of the numeric, boolean or timestamp column `name` as a
+    /// [ColumnArray], which is converted into a numpy array by
+    /// `numpy.asarray` without converting the values one by one.
+    ///
+    /// The column is looked up in the same way as [`Self::column_values`].
+    /// The nulls are converted into `NaN` in the floating point columns, and a
+    /// `ValueError` is raised if they are found in the other columns.
+    pub fn column_as_array(&self, py: Python<'_>, name: &str) -> PyResult<ColumnArray> {
+        let col_idx = self.column_idx(name)?;
+        let col_values = self
+            .rust_rows
+            .iter()
+            .map(|row| row.columns()[col_idx].value());
+        let data_type = col_values
+            .clone()
+            .find(|value| !value.is_null())
+            .map_or(DataType::Null, |value| value.data_type().into());
+
+        macro_rules! pack_values {
+            ($variant:ident, $null:expr, |$v:ident| $to_bytes:expr) => {{
+                let mut data = Vec::new();
+                for value in col_values {
+                    match value {
+                        RustValue::$variant($v) => data.extend_from_slice(&$to_bytes),
+                        RustValue::Null => match $null {
+                            Some(null) => data.extend_from_slice(&null),
+                            None => {
+                                return Err(PyValueError::new_err(format!(
+                                    "column has nulls, column:{name}"
+                                )))
+                            }
+                        },
+                        v => {
+                            return Err(PyTypeError::new_err(format!(
+                                "mixed data types in column, column:{name}, \
+                                 expect:{data_type:?}, actual:{:?}",
+                                v.data_type()
+                            )))
+                        }
+                    }
+                }
+                data
+            }};
+        }
+
+        let (dtype, data) = match data_type {
+            DataType::Timestamp => (
+                "datetime64[ms]",
+                pack_values!(Timestamp, None::<[u8; 8]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::Double | DataType::Null => (
+                "float64",
+                pack_values!(Double, Some(f64::NAN.to_ne_bytes()), |v| v.to_ne_bytes()),
+            ),
+            DataType::Float => (
+                "float32",
+                pack_values!(Float, Some(f32::NAN.to_ne_bytes()), |v| v.to_ne_bytes()),
+            ),
+            DataType::UInt64 => (
+                "uint64",
+                pack_values!(UInt64, None::<[u8; 8]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::UInt32 => (
+                "uint32",
+                pack_values!(UInt32, None::<[u8; 4]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::UInt16 => (
+                "uint16",
+                pack_values!(UInt16, None::<[u8; 2]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::UInt8 => (
+                "uint8",
+                pack_values!(UInt8, None::<[u8; 1]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::Int64 => (
+                "int64",
+                pack_values!(Int64, None::<[u8; 8]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::Int32 => (
+                "int32",
+                pack_values!(Int32, None::<[u8; 4]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::Int16 => (
+                "int16",
+                pack_values!(Int16, None::<[u8; 2]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::Int8 => (
+                "int8",
+                pack_values!(Int8, None::<[u8; 1]>, |v| v.to_ne_bytes()),
+            ),
+            DataType::Boolean => (
+                "bool",
+                pack_values!(Boolean, None::<[u8; 1]>, |v| [*v as u8]),
+            ),
+            DataType::Varbinary | DataType::String => {
+                return Err(PyTypeError::new_err(format!(
+                    "column is not numeric, column:{name}, data_type:{data_type:?}"
+                )))
+            }
+        };
+
+        Ok(ColumnArray {
+            name: name.to_string(),
+            dtype,
+            len: self.rust_rows.len(),
+            data: PyBytes::new(py, &data).into(),
+        })
+    }
+
+    /// Get the values of the column at `col_idx` in all the rows.
+    pub fn column_values_by_idx(&self, py: Python<'_>, col_idx: usize) -> PyResult<Vec<PyObject>> {
+        let num_cols = self.rust_rows.first().map_or(0, |row| row.columns().len());
+        if col_idx >= num_cols {
+            return Err(PyIndexError::new_err(format!(
+                "column index out of range, index:{col_idx}, num_cols:{num_cols}"
+            )));
+        }
+
+        Ok(self.collect_column_values(py, col_idx))
+    }
+
+    /// Get the values of all the columns as a dict from the column name to the
+    /// list of the values, in the order of the columns.
+    ///
+    /// The column names are taken from the first row, and an empty dict is
+    /// returned if there are no rows.
+    pub fn to_columns<'p>(&self, py: Python<'p>) -> PyResult<&'p PyDict> {
+        let columns = PyDict::new(py);
+        if let Some(first_row) = self.rust_rows.first() {
+            for (col_idx, col) in first_row.columns().iter().enumerate() {
+                columns.set_item(col.name(), self.collect_column_values(py, col_idx))?;
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Convert the rows into a `pandas.DataFrame`.
+    ///
+    /// The column names are taken from the first row, and the conversion is
+    /// done column by column with the dtype determined by the [DataType] of
+    /// the column.
+    pub fn to_pandas(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let pandas = py.import("pandas").map_err(|e| {
+            PyImportError::new_err(format!("pandas is required by to_pandas, err:{e}"))
+        })?;
+
+        let data = PyDict::new(py);
+        if let Some(first_row) = self.rust_rows.first() {
+            for (col_idx, col) in first_row.columns().iter().enumerate() {
+                let col_values = self
+                    .rust_rows
+                    .iter()
+                    .map(|row| row.columns()[col_idx].value());
+                let series = make_pandas_series(py, pandas, col_values)?;
+                data.set_item(col.name(), series)?;
+            }
+        }
+
+        let df = pandas.call_method1("DataFrame", (data,))?;
+        Ok(df.into())
+    }
+
+    /// Convert the rows into a `pyarrow.RecordBatch`.
+    ///
+    /// The arrays are built column by column in rust, and handed over to
+    /// pyarrow through the Arrow C Data Interface without copying them again.
+    pub fn to_arrow(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let pyarrow = py.import("pyarrow").map_err(|e| {
+            PyImportError::new_err(format!("pyarrow is required by to_arrow, err:{e}"))
+        })?;
+
+        let batch = self.make_record_batch(HashMap::new())?;
+        let array_data = StructArray::from(batch).into_data();
+        let ffi_array = FFI_ArrowArray::new(&array_data);
+        let ffi_schema = FFI_ArrowSchema::try_from(array_data.data_type())
+            .map_err(|e| PyException::new_err(format!("failed to export schema, err:{e}")))?;
+        // The ownership of the exported data is moved to pyarrow by the import.
+        let batch = pyarrow.getattr("RecordBatch")?.call_method1(
+            "_import_from_c",
+            (addr_of!(ffi_array) as usize, addr_of!(ffi_schema) as usize),
+        )?;
+
+        Ok(batch.into())
+    }
+
+    /// Serialize the response into the Arrow IPC stream format, which can be
+    /// cached and loaded back by [`SqlQueryResponse::from_bytes`].
+    pub fn to_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        let metadata = HashMap::from([(
+            AFFECTED_ROWS_METADATA_KEY.to_string(),
+            self.affected_rows.to_string(),
+        )]);
+        let batch = self.make_record_batch(metadata)?;
+
+        let to_py_err = |e| PyException::new_err(format!("failed to serialize response, err:{e}"));
+        let mut writer = StreamWriter::try_new(Vec::new(), &batch.schema()).map_err(to_py_err)?;
+        writer.write(&batch).map_err(to_py_err)?;
+        let bytes = writer.into_inner().map_err(to_py_err)?;
+
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Load the response serialized by [`SqlQueryResponse::to_bytes`], and a
+    /// `ValueError` is raised if the `data` is invalid.
+    #[classmethod]
+    pub fn from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<SqlQueryResponse> {
+        let to_py_err =
+            |e: ArrowError| PyValueError::new_err(format!("invalid response bytes, err:{e}"));
+        let reader = StreamReader::try_new(data, None).map_err(to_py_err)?;
+        let affected_rows = match reader.schema().metadata().get(AFFECTED_ROWS_METADATA_KEY) {
+            Some(v) => v.parse().map_err(|e| {
+                PyValueError::new_err(format!("invalid affected rows, affected_rows:{v}, err:{e}"))
+            })?,
+            None => 0,
+        };
+
+        let mut rust_rows = Vec::new();
+        for batch in reader {
+            let batch = batch.map_err(to_py_err)?;
+            let mut row_values = vec![Vec::with_capacity(batch.num_columns()); batch.num_rows()];
+            for column in batch.columns() {
+                for (row, value) in row_values.iter_mut().zip(arrow_column_values(column)?) {
+                    row.push(value);
+                }
+            }
+            let col_idx_to_name = batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .collect();
+            rust_rows.extend(
+                RustRowBuilder {
+                    col_idx_to_name,
+                    row_values,
+                }
+                .build(),
+            );
+        }
+
+        Ok(SqlQueryResponse {
+            rust_rows: Arc::new(rust_rows),
+            affected_rows,
+        })
+    }
+
+    /// Format the rows as csv, which is written to the file at `path`, or
+    /// returned as a string if `path` is `None`.
+    ///
+    /// The nulls are left empty, the binaries are encoded in base64, and the
+    /// timestamps are formatted in ISO 8601 in UTC, or as milliseconds if
+    /// `timestamp_as_ms` is set. The header is taken from the first row.
+    #[args(
+        path = "None",
+        delimiter = "','",
+        header = "true",
+        timestamp_as_ms = "false"
+    )]
+    pub fn to_csv(
+        &self,
+        py: Python<'_>,
+        path: Option<String>,
+        delimiter: char,
+        header: bool,
+        timestamp_as_ms: bool,
+    ) -> PyResult<Option<String>> {
+        let mut csv = String::new();
+        let mut push_record = |fields: &mut dyn Iterator<Item = String>| {
+            for (idx, field) in fields.enumerate() {
+                if idx > 0 {
+                    csv.push(delimiter);
+                }
+                push_csv_field(&mut csv, &field, delimiter);
+            }
+            csv.push('\n');
+        };
+
+        if let (true, Some(first_row)) = (header, self.rust_rows.first()) {
+            push_record(&mut first_row.columns().iter().map(|c| c.name().to_string()));
+        }
+        for row in self.rust_rows.iter() {
+            push_record(
+                &mut row
+                    .columns()
+                    .iter()
+                    .map(|c| format_csv_value(c.value(), timestamp_as_ms)),
+            );
+        }
+
+        match path {
+            Some(path) => {
+                py.allow_threads(|| std::fs::write(path, csv))?;
+                Ok(None)
+            }
+            None => Ok(Some(csv)),
+        }
+    }
+
     pub fn __str__(&self) -> String {
         format!("{self:?}")
     }
 }
 
+fn format_csv_value(value: &RustValue, timestamp_as_ms: bool) -> String {
+    match value {
+        RustValue::Null => String::new(),
+        RustValue::Timestamp(v) if timestamp_as_ms => v.to_string(),
+        RustValue::Timestamp(v) => match DateTime::from_timestamp_millis(*v) {
+            Some(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            None => v.to_string(),
+        },
+        RustValue::Double(v) => v.to_string(),
+        RustValue::Float(v) => v.to_string(),
+        RustValue::Varbinary(v) => BASE64_STANDARD.encode(v),
+        RustValue::String(v) => v.clone(),
+        RustValue::UInt64(v) => v.to_string(),
+        RustValue::UInt32(v) => v.to_string(),
+        RustValue::UInt16(v) => v.to_string(),
+        RustValue::UInt8(v) => v.to_string(),
+        RustValue::Int64(v) => v.to_string(),
+        RustValue::Int32(v) => v.to_string(),
+        RustValue::Int16(v) => v.to_string(),
+        RustValue::Int8(v) => v.to_string(),
+        RustValue::Boolean(v) => v.to_string(),
+    }
+}
+
+/// Push the `field` into the `csv`, and it is quoted with the quotes inside
+/// doubled if it contains the delimiter, the quotes or the line breaks.
+fn push_csv_field(csv: &mut String, field: &str, delimiter: char) {
+    if !field.contains([delimiter, '"', '\n', '\r']) {
+        csv.push_str(field);
+        return;
+    }
+
+    csv.push('"');
+    for c in field.chars() {
+        if c == '"' {
+            csv.push('"');
+        }
+        csv.push(c);
+    }
+    csv.push('"');
+}
+
+/// Build an arrow array from the values of a column.
+///
+/// The [DataType] of the column is determined by its first non-null value,
+/// and a `TypeError` is raised if other values are of different types.
+fn make_arrow_column<'a>(
+    name: &str,
+    col_values: impl Iterator<Item = &'a RustValue> + Clone,
+) -> PyResult<(Field, ArrayRef)> {
+    let data_type = col_values
+        .clone()
+        .find(|value| !value.is_null())
+        .map_or(DataType::Null, |value| value.data_type().into());
+
+    macro_rules! build_array {
+        ($builder:ty, $variant:ident, |$v:ident| $append:expr) => {{
+            let mut builder = <$builder>::new();
+            for value in col_values {
+                match value {
+                    RustValue::Null => builder.append_null(),
+                    RustValue::$variant($v) => builder.append_value($append),
+                    v => {
+                        return Err(PyTypeError::new_err(format!(
+                            "mixed data types in column, column:{name}, expect:{data_type:?}, \
+                             actual:{:?}",
+                            v.data_type()
+                        )))
+                    }
+                }
+            }
+            Arc::new(builder.finish()) as ArrayRef
+        }};
+    }
+
+    let array = match data_type {
+        DataType::Null => Arc::new(NullArray::new(col_values.count())) as ArrayRef,
+        DataType::Timestamp => build_array!(TimestampMillisecondBuilder, Timestamp, |v| *v),
+        DataType::Double => build_array!(Float64Builder, Double, |v| *v),
+        DataType::Float => build_array!(Float32Builder, Float, |v| *v),
+        DataType::Varbinary => build_array!(BinaryBuilder, Varbinary, |v| v),
+        DataType::String => build_array!(StringBuilder, String, |v| v),
+        DataType::UInt64 => build_array!(UInt64Builder, UInt64, |v| *v),
+        DataType::UInt32 => build_array!(UInt32Builder, UInt32, |v| *v),
+        DataType::UInt16 => build_array!(UInt16Builder, UInt16, |v| *v),
+        DataType::UInt8 => build_array!(UInt8Builder, UInt8, |v| *v),
+        DataType::Int64 => build_array!(Int64Builder, Int64, |v| *v),
+        DataType::Int32 => build_array!(Int32Builder, Int32, |v| *v),
+        DataType::Int16 => build_array!(Int16Builder, Int16, |v| *v),
+        DataType::Int8 => build_array!(Int8Builder, Int8, |v| *v),
+        DataType::Boolean => build_array!(BooleanBuilder, Boolean, |v| *v),
+    };
+    let field = Field::new(name, array.data_type().clone(), true);
+
+    Ok((field, array))
+}
+
+/// The key of the schema metadata keeping the affected rows of a serialized
+/// [SqlQueryResponse].
+const AFFECTED_ROWS_METADATA_KEY: &str = "horaedb.affected_rows";
+
+/// Convert the arrow array built by [make_arrow_column] back into the values.
+fn arrow_column_values(array: &ArrayRef) -> PyResult<Vec<RustValue>> {
+    macro_rules! collect_values {
+        ($array:ty, $variant:ident, |$v:ident| $convert:expr) => {{
+            let array = array.as_any().downcast_ref::<$array>().unwrap();
+            array
+                .iter()
+                .map(|v| v.map_or(RustValue::Null, |$v| RustValue::$variant($convert)))
+                .collect()
+        }};
+    }
+
+    let values = match array.data_type() {
+        ArrowDataType::Null => vec![RustValue::Null; array.len()],
+        ArrowDataType::Timestamp(TimeUnit::Millisecond, _) => {
+            collect_values!(TimestampMillisecondArray, Timestamp, |v| v)
+        }
+        ArrowDataType::Float64 => collect_values!(Float64Array, Double, |v| v),
+        ArrowDataType::Float32 => collect_values!(Float32Array, Float, |v| v),
+        ArrowDataType::Binary => collect_values!(BinaryArray, Varbinary, |v| v.to_vec()),
+        ArrowDataType::Utf8 => collect_values!(StringArray, String, |v| v.to_string()),
+        ArrowDataType::UInt64 => collect_values!(UInt64Array, UInt64, |v| v),
+        ArrowDataType::UInt32 => collect_values!(UInt32Array, UInt32, |v| v),
+        ArrowDataType::UInt16 => collect_values!(UInt16Array, UInt16, |v| v),
+        ArrowDataType::UInt8 => collect_values!(UInt8Array, UInt8, |v| v),
+        ArrowDataType::Int64 => collect_values!(Int64Array, Int64, |v| v),
+        ArrowDataType::Int32 => collect_values!(Int32Array, Int32, |v| v),
+        ArrowDataType::Int16 => collect_values!(Int16Array, Int16, |v| v),
+        ArrowDataType::Int8 => collect_values!(Int8Array, Int8, |v| v),
+        ArrowDataType::Boolean => collect_values!(BooleanArray, Boolean, |v| v),
+        data_type => {
+            return Err(PyValueError::new_err(format!(
+                "unsupported arrow data type, data_type:{data_type}"
+            )))
+        }
+    };
+
+    Ok(values)
+}
+
+/// Build a `pandas.Series` from the values of a column.
+///
+/// The [DataType] of the column is determined by its first non-null value.
+fn make_pandas_series<'p, 'a>(
+    py: Python<'p>,
+    pandas: &'p PyModule,
+    col_values: impl Iterator<Item = &'a RustValue>,
+) -> PyResult<&'p PyAny> {
+    let mut data_type = DataType::Null;
+    let mut nullable = false;
+    let objects = col_values
+        .map(|value| {
+            if value.is_null() {
+                nullable = true;
+            } else if matches!(data_type, DataType::Null) {
+                data_type = value.data_type().into();
+            }
+            value_to_object(py, value)
+        })
+        .collect::<Vec<_>>();
+
+    match data_type {
+        DataType::Timestamp => {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("unit", "ms")?;
+            let timestamps = pandas.call_method("to_datetime", (objects,), Some(kwargs))?;
+            pandas.call_method1("Series", (timestamps,))
+        }
+        _ => {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("dtype", data_type.pandas_dtype(nullable))?;
+            pandas.call_method("Series", (objects,), Some(kwargs))
+        }
+    }
+}
+
+/// The iterator over the rows as dicts.
+#[pyclass]
+pub struct DictIter {
+    rust_rows: Arc<Vec<RustRow>>,
+    next_row_idx: usize,
+    /// The column names shared by the rows, so they are built only once.
+    col_names: Vec<Py<PyString>>,
+}
+
+#[pymethods]
+impl DictIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>, py: Python<'_>) -> PyResult<Option<Py<PyDict>>> {
+        if slf.next_row_idx >= slf.rust_rows.len() {
+            return Ok(None);
+        }
+
+        let row_idx = slf.next_row_idx;
+        slf.next_row_idx += 1;
+        let dict = PyDict::new(py);
+        let cols = slf.rust_rows[row_idx].columns();
+        for (col_name, col) in slf.col_names.iter().zip(cols) {
+            dict.set_item(col_name, value_to_object(py, col.value()))?;
+        }
+
+        Ok(Some(dict.into()))
+    }
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub struct RowIter {
@@ -164,6 +921,48 @@ impl From<RustSqlQueryResponse> for SqlQueryResponse {
     }
 }
 
+/// The values of a column packed in the native layout of its numpy dtype,
+/// which supports `numpy.asarray` through `__array__`.
+#[pyclass]
+pub struct ColumnArray {
+    name: String,
+    dtype: &'static str,
+    len: usize,
+    data: Py<PyBytes>,
+}
+
+#[pymethods]
+impl ColumnArray {
+    #[getter]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The numpy dtype of the values, e.g. `"float64"`.
+    #[getter]
+    pub fn dtype(&self) -> &str {
+        self.dtype
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.len
+    }
+
+    /// Build the numpy array over the packed values, which is read-only and
+    /// converted to the `dtype` if given.
+    #[args(dtype = "None")]
+    pub fn __array__(&self, py: Python<'_>, dtype: Option<&PyAny>) -> PyResult<PyObject> {
+        let numpy = py.import("numpy").map_err(|e| {
+            PyImportError::new_err(format!("numpy is required by __array__, err:{e}"))
+        })?;
+        let array = numpy.call_method1("frombuffer", (self.data.as_ref(py), self.dtype))?;
+        match dtype {
+            Some(dtype) => Ok(array.call_method1("astype", (dtype,))?.into()),
+            None => Ok(array.into()),
+        }
+    }
+}
+
 /// The data type definitions for read/write protocol.
 #[pyclass]
 #[derive(Clone, Copy, Debug)]
@@ -207,6 +1006,188 @@ impl From<RustDataType> for DataType {
     }
 }
 
+#[pymethods]
+impl DataType {
+    /// The lowercase name of the type, e.g. `"int64"`.
+    pub fn __str__(&self) -> &'static str {
+        match self {
+            DataType::Null => "null",
+            DataType::Timestamp => "timestamp",
+            DataType::Double => "double",
+            DataType::Float => "float",
+            DataType::Varbinary => "varbinary",
+            DataType::String => "string",
+            DataType::UInt64 => "uint64",
+            DataType::UInt32 => "uint32",
+            DataType::UInt16 => "uint16",
+            DataType::UInt8 => "uint8",
+            DataType::Int64 => "int64",
+            DataType::Int32 => "int32",
+            DataType::Int16 => "int16",
+            DataType::Int8 => "int8",
+            DataType::Boolean => "boolean",
+        }
+    }
+
+    /// Whether the type is an integer or a floating point type, and the
+    /// timestamp and the boolean are not numeric.
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_floating()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            DataType::UInt64
+                | DataType::UInt32
+                | DataType::UInt16
+                | DataType::UInt8
+                | DataType::Int64
+                | DataType::Int32
+                | DataType::Int16
+                | DataType::Int8
+        )
+    }
+
+    /// Whether the type is a numeric type able to represent negative values.
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            DataType::Int64 | DataType::Int32 | DataType::Int16 | DataType::Int8
+        ) || self.is_floating()
+    }
+
+    pub fn is_floating(&self) -> bool {
+        matches!(self, DataType::Double | DataType::Float)
+    }
+
+    /// Whether the type is a string or a varbinary.
+    pub fn is_string_like(&self) -> bool {
+        matches!(self, DataType::String | DataType::Varbinary)
+    }
+}
+
+impl DataType {
+    /// The dtype used for the column of this type in a `pandas.DataFrame`.
+    ///
+    /// The nullable extension dtypes are used for the integer and boolean
+    /// columns containing nulls, which can't be represented by numpy dtypes.
+    fn pandas_dtype(self, nullable: bool) -> &'static str {
+        match (self, nullable) {
+            (DataType::Timestamp, _) => "datetime64[ns]",
+            (DataType::Double, _) => "float64",
+            (DataType::Float, _) => "float32",
+            (DataType::UInt64, false) => "uint64",
+            (DataType::UInt64, true) => "UInt64",
+            (DataType::UInt32, false) => "uint32",
+            (DataType::UInt32, true) => "UInt32",
+            (DataType::UInt16, false) => "uint16",
+            (DataType::UInt16, true) => "UInt16",
+            (DataType::UInt8, false) => "uint8",
+            (DataType::UInt8, true) => "UInt8",
+            (DataType::Int64, false) => "int64",
+            (DataType::Int64, true) => "Int64",
+            (DataType::Int32, false) => "int32",
+            (DataType::Int32, true) => "Int32",
+            (DataType::Int16, false) => "int16",
+            (DataType::Int16, true) => "Int16",
+            (DataType::Int8, false) => "int8",
+            (DataType::Int8, true) => "Int8",
+            (DataType::Boolean, false) => "bool",
+            (DataType::Boolean, true) => "boolean",
+            (DataType::Null | DataType::Varbinary | DataType::String, _) => "object",
+        }
+    }
+}
+
+fn type_mismatch(expected: &str, value: &RustValue) -> PyErr {
+    PyTypeError::new_err(format!(
+        "column is not {expected}, data_type:{:?}",
+        value.data_type()
+    ))
+}
+
+/// Extract the python int as the integer type named `type_name`, and an
+/// `OverflowError` containing the value is raised if it doesn't fit.
+fn extract_int<'a, T: FromPyObject<'a>>(val: &'a PyAny, type_name: &str) -> PyResult<T> {
+    val.extract().map_err(|e| {
+        if e.is_instance_of::<PyOverflowError>(val.py()) {
+            PyOverflowError::new_err(format!("value out of range for {type_name}, value:{val}"))
+        } else {
+            e
+        }
+    })
+}
+
+/// Parse the decimal string as the integer type named `type_name`.
+fn parse_int<T: FromStr<Err = ParseIntError>>(val: &str, type_name: &str) -> PyResult<T> {
+    val.trim()
+        .parse()
+        .map_err(|e: ParseIntError| match e.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                PyOverflowError::new_err(format!("value out of range for {type_name}, value:{val}"))
+            }
+            _ => PyValueError::new_err(format!("invalid {type_name}, value:{val}, err:{e}")),
+        })
+}
+
+/// Convert the [RustValue] into the corresponding python object.
+fn value_to_object(py: Python<'_>, value: &RustValue) -> PyObject {
+    match value {
+        RustValue::Null => py.None(),
+        RustValue::Timestamp(v) => (*v).to_object(py),
+        RustValue::Double(v) => (*v).to_object(py),
+        RustValue::Float(v) => (*v).to_object(py),
+        RustValue::Varbinary(v) => v.as_slice().to_object(py),
+        RustValue::String(v) => v.as_str().to_object(py),
+        RustValue::UInt64(v) => (*v).to_object(py),
+        RustValue::UInt32(v) => (*v).to_object(py),
+        RustValue::UInt16(v) => (*v).to_object(py),
+        RustValue::UInt8(v) => (*v).to_object(py),
+        RustValue::Int64(v) => (*v).to_object(py),
+        RustValue::Int32(v) => (*v).to_object(py),
+        RustValue::Int16(v) => (*v).to_object(py),
+        RustValue::Int8(v) => (*v).to_object(py),
+        RustValue::Boolean(v) => (*v).to_object(py),
+    }
+}
+
+/// The pickled state of a [RustValue], the code of its [DataType] and its
+/// python object.
+type ValueState = (u8, PyObject);
+
+fn value_to_state(py: Python<'_>, value: &RustValue) -> ValueState {
+    let data_type = DataType::from(value.data_type());
+    (data_type as u8, value_to_object(py, value))
+}
+
+fn value_from_state(state: (u8, &PyAny)) -> PyResult<RustValue> {
+    let (code, obj) = state;
+    let value = match code {
+        c if c == DataType::Null as u8 => RustValue::Null,
+        c if c == DataType::Timestamp as u8 => RustValue::Timestamp(obj.extract()?),
+        c if c == DataType::Double as u8 => RustValue::Double(obj.extract()?),
+        c if c == DataType::Float as u8 => RustValue::Float(obj.extract()?),
+        c if c == DataType::Varbinary as u8 => RustValue::Varbinary(obj.extract()?),
+        c if c == DataType::String as u8 => RustValue::String(obj.extract()?),
+        c if c == DataType::UInt64 as u8 => RustValue::UInt64(obj.extract()?),
+        c if c == DataType::UInt32 as u8 => RustValue::UInt32(obj.extract()?),
+        c if c == DataType::UInt16 as u8 => RustValue::UInt16(obj.extract()?),
+        c if c == DataType::UInt8 as u8 => RustValue::UInt8(obj.extract()?),
+        c if c == DataType::Int64 as u8 => RustValue::Int64(obj.extract()?),
+        c if c == DataType::Int32 as u8 => RustValue::Int32(obj.extract()?),
+        c if c == DataType::Int16 as u8 => RustValue::Int16(obj.extract()?),
+        c if c == DataType::Int8 as u8 => RustValue::Int8(obj.extract()?),
+        c if c == DataType::Boolean as u8 => RustValue::Boolean(obj.extract()?),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "invalid data type in pickled value, code:{code}"
+            )))
+        }
+    };
+    Ok(value)
+}
+
 /// A column of data returned from a sql query.
 #[pyclass]
 #[derive(Clone, Debug)]
@@ -220,27 +1201,142 @@ impl Column {
     fn get_rust_col(&self) -> &RustColumn {
         &self.rust_rows[self.row_idx].columns()[self.col_idx]
     }
+
+    fn get_timestamp(&self) -> PyResult<TimestampMs> {
+        match self.get_rust_col().value() {
+            RustValue::Timestamp(v) => Ok(*v),
+            v => Err(type_mismatch("a timestamp", v)),
+        }
+    }
 }
 
 #[pymethods]
 impl Column {
     pub fn value(&self, py: Python<'_>) -> PyObject {
+        value_to_object(py, self.get_rust_col().value())
+    }
+
+    /// Whether the value is null, which can be checked without converting the
+    /// value.
+    pub fn is_null(&self) -> bool {
+        matches!(self.get_rust_col().value(), RustValue::Null)
+    }
+
+    /// Get the value of a timestamp column as a UTC `datetime.datetime`.
+    pub fn as_datetime<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        timestamp_ms_to_datetime(py, self.get_timestamp()?)
+    }
+
+    /// Get the value of a timestamp column in milliseconds since the epoch.
+    pub fn timestamp_ms(&self) -> PyResult<TimestampMs> {
+        self.get_timestamp()
+    }
+
+    /// Get the value of a timestamp column as a UTC `datetime.datetime`, which
+    /// is an alias of [`Column::as_datetime`] named after
+    /// [`Column::timestamp_ms`].
+    pub fn timestamp_datetime<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        self.as_datetime(py)
+    }
+
+    /// Get the value of a numeric column as a `decimal.Decimal`, and `None` is
+    /// returned for null.
+    ///
+    /// The floats are converted from their shortest string representations, so
+    /// the decimal is the same as the printed value rather than the exact
+    /// binary value.
+    pub fn value_as_decimal(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let repr = match self.get_rust_col().value() {
+            RustValue::Null => return Ok(py.None()),
+            RustValue::Double(v) => v.to_string(),
+            RustValue::Float(v) => v.to_string(),
+            RustValue::UInt64(v) => v.to_string(),
+            RustValue::UInt32(v) => v.to_string(),
+            RustValue::UInt16(v) => v.to_string(),
+            RustValue::UInt8(v) => v.to_string(),
+            RustValue::Int64(v) => v.to_string(),
+            RustValue::Int32(v) => v.to_string(),
+            RustValue::Int16(v) => v.to_string(),
+            RustValue::Int8(v) => v.to_string(),
+            v => {
+                return Err(PyTypeError::new_err(format!(
+                    "column is not numeric, data_type:{:?}",
+                    v.data_type()
+                )))
+            }
+        };
+
+        let decimal = py.import("decimal")?.getattr("Decimal")?.call1((repr,))?;
+        Ok(decimal.into())
+    }
+
+    /// Get the value of an integer column as an `int`, and `None` is returned
+    /// for null.
+    ///
+    /// The typed getters raise a `TypeError` if the column is of other types.
+    pub fn as_int(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match self.get_rust_col().value() {
+            RustValue::Null => Ok(py.None()),
+            v @ (RustValue::UInt64(_)
+            | RustValue::UInt32(_)
+            | RustValue::UInt16(_)
+            | RustValue::UInt8(_)
+            | RustValue::Int64(_)
+            | RustValue::Int32(_)
+            | RustValue::Int16(_)
+            | RustValue::Int8(_)) => Ok(value_to_object(py, v)),
+            v => Err(type_mismatch("an integer", v)),
+        }
+    }
+
+    /// Get the value of a float column as a `float`, and `None` is returned
+    /// for null.
+    pub fn as_float(&self) -> PyResult<Option<f64>> {
+        match self.get_rust_col().value() {
+            RustValue::Null => Ok(None),
+            RustValue::Double(v) => Ok(Some(*v)),
+            RustValue::Float(v) => Ok(Some(*v as f64)),
+            v => Err(type_mismatch("a float", v)),
+        }
+    }
+
+    /// Get the value of a string column as a `str`, and `None` is returned
+    /// for null.
+    pub fn as_str(&self) -> PyResult<Option<&str>> {
+        match self.get_rust_col().value() {
+            RustValue::Null => Ok(None),
+            RustValue::String(v) => Ok(Some(v)),
+            v => Err(type_mismatch("a string", v)),
+        }
+    }
+
+    /// Get the value of a boolean column as a `bool`, and `None` is returned
+    /// for null.
+    pub fn as_bool(&self) -> PyResult<Option<bool>> {
+        match self.get_rust_col().value() {
+            RustValue::Null => Ok(None),
+            RustValue::Boolean(v) => Ok(Some(*v)),
+            v => Err(type_mismatch("a boolean", v)),
+        }
+    }
+
+    /// Get the value of a varbinary column as `bytes`, and `None` is returned
+    /// for null.
+    pub fn as_bytes(&self, py: Python<'_>) -> PyResult<PyObject> {
+        match self.get_rust_col().value() {
+            RustValue::Null => Ok(py.None()),
+            v @ RustValue::Varbinary(_) => Ok(value_to_object(py, v)),
+            v => Err(type_mismatch("a varbinary", v)),
+        }
+    }
+
+    /// Get the value of a uint8 column as a `bytes` of the single byte, for
+    /// the columns holding byte data, and `None` is returned for null.
+    pub fn as_uint8_bytes<'p>(&self, py: Python<'p>) -> PyResult<Option<&'p PyBytes>> {
         match self.get_rust_col().value() {
-            RustValue::Null => py.None(),
-            RustValue::Timestamp(v) => (*v).to_object(py),
-            RustValue::Double(v) => (*v).to_object(py),
-            RustValue::Float(v) => (*v).to_object(py),
-            RustValue::Varbinary(v) => v.as_slice().to_object(py),
-            RustValue::String(v) => v.as_str().to_object(py),
-            RustValue::UInt64(v) => (*v).to_object(py),
-            RustValue::UInt32(v) => (*v).to_object(py),
-            RustValue::UInt16(v) => (*v).to_object(py),
-            RustValue::UInt8(v) => (*v).to_object(py),
-            RustValue::Int64(v) => (*v).to_object(py),
-            RustValue::Int32(v) => (*v).to_object(py),
-            RustValue::Int16(v) => (*v).to_object(py),
-            RustValue::Int8(v) => (*v).to_object(py),
-            RustValue::Boolean(v) => (*v).to_object(py),
+            RustValue::Null => Ok(None),
+            RustValue::UInt8(v) => Ok(Some(PyBytes::new(py, &[*v]))),
+            v => Err(type_mismatch("a uint8", v)),
         }
     }
 
@@ -252,6 +1348,21 @@ impl Column {
         self.get_rust_col().name()
     }
 
+    /// Columns are equal if they have the same name and value, no matter which
+    /// responses they come from.
+    pub fn __richcmp__(&self, py: Python<'_>, other: &PyAny, op: CompareOp) -> PyObject {
+        let other = match other.extract::<PyRef<Column>>() {
+            Ok(other) => other,
+            Err(_) => return py.NotImplemented(),
+        };
+        let eq = columns_eq(self.get_rust_col(), other.get_rust_col());
+        match op {
+            CompareOp::Eq => eq.into_py(py),
+            CompareOp::Ne => (!eq).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
     pub fn __str__(&self) -> String {
         let rust_col = self.get_rust_col();
         format!("{rust_col:?}")
@@ -314,33 +1425,111 @@ impl Row {
         }
     }
 
-    pub fn column_by_idx(&self, col_idx: usize) -> Option<Column> {
-        let row = &self.rust_rows[self.row_idx];
-
-        if col_idx >= row.columns().len() {
-            None
+    /// Get the [Column] at `col_idx`.
+    ///
+    /// Negative indexes count from the end, like a python sequence, and an
+    /// `IndexError` is raised if the index is out of range after that.
+    pub fn column_by_idx(&self, col_idx: isize) -> PyResult<Column> {
+        let num_cols = self.rust_rows[self.row_idx].columns().len() as isize;
+        let idx = if col_idx < 0 {
+            col_idx + num_cols
         } else {
-            let col = Column {
-                row_idx: self.row_idx,
-                col_idx,
-                rust_rows: self.rust_rows.clone(),
-            };
-            Some(col)
+            col_idx
+        };
+        if !(0..num_cols).contains(&idx) {
+            return Err(PyIndexError::new_err(format!(
+                "column index out of range, index:{col_idx}, num_cols:{num_cols}"
+            )));
         }
+
+        Ok(Column {
+            row_idx: self.row_idx,
+            col_idx: idx as usize,
+            rust_rows: self.rust_rows.clone(),
+        })
     }
 
     pub fn num_cols(&self) -> usize {
         self.rust_rows[self.row_idx].columns().len()
     }
 
-    pub fn iter_columns(&self) -> ColumnIter {
-        ColumnIter {
-            rust_rows: self.rust_rows.clone(),
+    pub fn __contains__(&self, col_name: &str) -> bool {
+        self.rust_rows[self.row_idx]
+            .columns()
+            .iter()
+            .any(|c| c.name() == col_name)
+    }
+
+    /// Get the value of the column named `col_name`, and the `default` is
+    /// returned if there is no such column, like `dict.get`.
+    #[args(default = "None")]
+    pub fn get(&self, py: Python<'_>, col_name: &str, default: Option<PyObject>) -> PyObject {
+        self.rust_rows[self.row_idx]
+            .columns()
+            .iter()
+            .find(|c| c.name() == col_name)
+            .map(|c| value_to_object(py, c.value()))
+            .unwrap_or_else(|| default.unwrap_or_else(|| py.None()))
+    }
+
+    /// The names of the columns in order.
+    pub fn keys(&self) -> Vec<&str> {
+        self.rust_rows[self.row_idx]
+            .columns()
+            .iter()
+            .map(|c| c.name())
+            .collect()
+    }
+
+    /// The pairs of the column name and value in order.
+    pub fn items(&self, py: Python<'_>) -> Vec<(&str, PyObject)> {
+        self.rust_rows[self.row_idx]
+            .columns()
+            .iter()
+            .map(|c| (c.name(), value_to_object(py, c.value())))
+            .collect()
+    }
+
+    /// Convert the row into a dict from the column name to its value.
+    ///
+    /// If multiple columns share the same name, the last one wins.
+    pub fn to_dict<'p>(&self, py: Python<'p>) -> PyResult<&'p PyDict> {
+        let dict = PyDict::new(py);
+        for col in self.rust_rows[self.row_idx].columns() {
+            dict.set_item(col.name(), value_to_object(py, col.value()))?;
+        }
+
+        Ok(dict)
+    }
+
+    pub fn iter_columns(&self) -> ColumnIter {
+        ColumnIter {
+            rust_rows: self.rust_rows.clone(),
             row_idx: self.row_idx,
             next_col_idx: 0,
         }
     }
 
+    /// Rows are equal if they have the equal columns in the same order, no
+    /// matter which responses they come from.
+    pub fn __richcmp__(&self, py: Python<'_>, other: &PyAny, op: CompareOp) -> PyObject {
+        let other = match other.extract::<PyRef<Row>>() {
+            Ok(other) => other,
+            Err(_) => return py.NotImplemented(),
+        };
+        let (cols, other_cols) = (
+            self.rust_rows[self.row_idx].columns(),
+            other.rust_rows[other.row_idx].columns(),
+        );
+        let eq = cols.len() == other_cols.len()
+            && cols.iter().zip(other_cols).all(|(a, b)| columns_eq(a, b));
+        match op {
+            CompareOp::Eq => eq.into_py(py),
+            CompareOp::Ne => (!eq).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
     pub fn __str__(&self) -> String {
         let rust_row = &self.rust_rows[self.row_idx];
         format!("{rust_row:?}")
@@ -354,6 +1543,81 @@ pub struct Value {
     raw_val: RustValue,
 }
 
+/// The floats are compared and hashed by their bit patterns to keep `__eq__`
+/// consistent with `__hash__`, so a NaN equals to the NaN with the same bits,
+/// while `0.0` doesn't equal to `-0.0`.
+#[pymethods]
+impl Value {
+    pub fn data_type(&self) -> DataType {
+        self.raw_val.data_type().into()
+    }
+
+    pub fn __richcmp__(&self, py: Python<'_>, other: &PyAny, op: CompareOp) -> PyObject {
+        let other = match other.extract::<PyRef<Value>>() {
+            Ok(other) => other,
+            Err(_) => return py.NotImplemented(),
+        };
+        match op {
+            CompareOp::Eq => self.bitwise_eq(&other).into_py(py),
+            CompareOp::Ne => (!self.bitwise_eq(&other)).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    pub fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_value(&self.raw_val, &mut hasher);
+        hasher.finish()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("Value({:?})", self.raw_val)
+    }
+}
+
+impl Value {
+    fn bitwise_eq(&self, other: &Value) -> bool {
+        values_bitwise_eq(&self.raw_val, &other.raw_val)
+    }
+}
+
+/// Hash the value with the floats hashed by bits, which is consistent with
+/// [values_bitwise_eq].
+fn hash_value(value: &RustValue, hasher: &mut impl Hasher) {
+    (value.data_type() as u8).hash(hasher);
+    match value {
+        RustValue::Null => {}
+        RustValue::Timestamp(v) => v.hash(hasher),
+        RustValue::Double(v) => v.to_bits().hash(hasher),
+        RustValue::Float(v) => v.to_bits().hash(hasher),
+        RustValue::Varbinary(v) => v.hash(hasher),
+        RustValue::String(v) => v.hash(hasher),
+        RustValue::UInt64(v) => v.hash(hasher),
+        RustValue::UInt32(v) => v.hash(hasher),
+        RustValue::UInt16(v) => v.hash(hasher),
+        RustValue::UInt8(v) => v.hash(hasher),
+        RustValue::Int64(v) => v.hash(hasher),
+        RustValue::Int32(v) => v.hash(hasher),
+        RustValue::Int16(v) => v.hash(hasher),
+        RustValue::Int8(v) => v.hash(hasher),
+        RustValue::Boolean(v) => v.hash(hasher),
+    }
+}
+
+/// Compare the values with the floats compared by bits, so that a value is
+/// always equal to itself even if it is a NaN.
+fn values_bitwise_eq(a: &RustValue, b: &RustValue) -> bool {
+    match (a, b) {
+        (RustValue::Double(a), RustValue::Double(b)) => a.to_bits() == b.to_bits(),
+        (RustValue::Float(a), RustValue::Float(b)) => a.to_bits() == b.to_bits(),
+        (a, b) => a == b,
+    }
+}
+
+fn columns_eq(a: &RustColumn, b: &RustColumn) -> bool {
+    a.name() == b.name() && values_bitwise_eq(a.value(), b.value())
+}
+
 /// Builder for a [Value].
 #[pyclass]
 #[derive(Clone, Debug, Default)]
@@ -378,6 +1642,22 @@ impl ValueBuilder {
         }
     }
 
+    /// Build a timestamp [Value] from a `datetime.datetime` or `datetime.date`.
+    ///
+    /// A naive datetime is assumed to be in UTC unless `assume_utc` is false,
+    /// in which case the local timezone is used.
+    #[args(assume_utc = "true")]
+    pub fn timestamp_from_datetime(
+        &self,
+        py: Python<'_>,
+        dt: &PyAny,
+        assume_utc: bool,
+    ) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::Timestamp(datetime_to_timestamp_ms(py, dt, assume_utc)?),
+        })
+    }
+
     pub fn double(&self, val: f64) -> Value {
         Value {
             raw_val: RustValue::Double(val),
@@ -396,58 +1676,102 @@ impl ValueBuilder {
         }
     }
 
-    pub fn varbinary(&self, val: Vec<u8>) -> Value {
-        Value {
-            raw_val: RustValue::Varbinary(val),
-        }
+    /// Build the [Value]s from the elements of a 1-D numpy array, whose
+    /// [DataType] is determined by the dtype of the array once, while the
+    /// elements are still converted one by one through `tolist`.
+    ///
+    /// The bool, int, uint and float dtypes are supported, and `TypeError` is
+    /// raised for the others. The nullable dtypes of pandas, e.g. `Int64` and
+    /// `boolean`, are supported too, whose missing values become nulls.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_numpy(&self, array: &PyAny) -> PyResult<Vec<Value>> {
+        let values = values_from_numpy(array)?.ok_or_else(|| {
+            let dtype = array
+                .getattr("dtype")
+                .and_then(|dtype| dtype.str())
+                .map(|dtype| dtype.to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+            PyTypeError::new_err(format!("unsupported numpy dtype, dtype:{dtype}"))
+        })?;
+
+        Ok(values
+            .into_iter()
+            .map(|raw_val| Value { raw_val })
+            .collect())
     }
 
-    pub fn uint64(&self, val: u64) -> Value {
-        Value {
-            raw_val: RustValue::UInt64(val),
-        }
+    /// Build a varbinary [Value] from a `bytes`, `bytearray` or `memoryview`,
+    /// whose content is copied at once, or a sequence of ints.
+    pub fn varbinary(&self, val: &PyAny) -> PyResult<Value> {
+        let bytes = match extract_bytes(val)? {
+            Some(bytes) => bytes,
+            None => val.extract()?,
+        };
+        Ok(Value {
+            raw_val: RustValue::Varbinary(bytes),
+        })
     }
 
-    pub fn uint32(&self, val: u32) -> Value {
-        Value {
-            raw_val: RustValue::UInt32(val),
-        }
+    pub fn uint64(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::UInt64(extract_int(val, "uint64")?),
+        })
     }
 
-    pub fn uint16(&self, val: i16) -> Value {
-        Value {
-            raw_val: RustValue::Int16(val),
-        }
+    pub fn uint32(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::UInt32(extract_int(val, "uint32")?),
+        })
     }
 
-    pub fn uint8(&self, val: u8) -> Value {
-        Value {
-            raw_val: RustValue::UInt8(val),
-        }
+    pub fn uint16(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::UInt16(extract_int(val, "uint16")?),
+        })
     }
 
-    pub fn int64(&self, val: i64) -> Value {
-        Value {
-            raw_val: RustValue::Int64(val),
-        }
+    pub fn uint8(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::UInt8(extract_int(val, "uint8")?),
+        })
     }
 
-    pub fn int32(&self, val: i32) -> Value {
-        Value {
-            raw_val: RustValue::Int32(val),
-        }
+    pub fn int64(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::Int64(extract_int(val, "int64")?),
+        })
     }
 
-    pub fn int16(&self, val: u16) -> Value {
-        Value {
-            raw_val: RustValue::UInt16(val),
-        }
+    /// Build a uint64 [Value] from its decimal string.
+    pub fn uint64_from_str(&self, val: &str) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::UInt64(parse_int(val, "uint64")?),
+        })
     }
 
-    pub fn int8(&self, val: i8) -> Value {
-        Value {
-            raw_val: RustValue::Int8(val),
-        }
+    /// Build an int64 [Value] from its decimal string.
+    pub fn int64_from_str(&self, val: &str) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::Int64(parse_int(val, "int64")?),
+        })
+    }
+
+    pub fn int32(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::Int32(extract_int(val, "int32")?),
+        })
+    }
+
+    pub fn int16(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::Int16(extract_int(val, "int16")?),
+        })
+    }
+
+    pub fn int8(&self, val: &PyAny) -> PyResult<Value> {
+        Ok(Value {
+            raw_val: RustValue::Int8(extract_int(val, "int8")?),
+        })
     }
 
     pub fn bool(&self, val: bool) -> Value {
@@ -455,6 +1779,29 @@ impl ValueBuilder {
             raw_val: RustValue::Boolean(val),
         }
     }
+
+    /// Build a [Value] whose type is inferred from the python object.
+    ///
+    /// The mapping is: `None` -> `Null`, `bool` -> `Boolean`, `int` ->
+    /// `Int64`, `float` -> `Double`, `str` -> `String`, `bytes`/`bytearray`
+    /// -> `Varbinary` and `datetime` -> `Timestamp`.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_object(&self, py: Python<'_>, obj: &PyAny) -> PyResult<Value> {
+        let raw_val = if obj.is_none() {
+            RustValue::Null
+        } else if is_datetime(py, obj)? {
+            RustValue::Timestamp(datetime_to_timestamp_ms(py, obj, true)?)
+        } else if let Some(val) = infer_value(obj)? {
+            val
+        } else {
+            return Err(PyTypeError::new_err(format!(
+                "unsupported value type:{}",
+                obj.get_type().name()?
+            )));
+        };
+
+        Ok(Value { raw_val })
+    }
 }
 
 impl From<Value> for RustValue {
@@ -468,6 +1815,298 @@ impl From<Value> for RustValue {
 #[derive(Clone, Debug)]
 pub struct Point {
     rust_point: RustPoint,
+    /// The names of the tags and the fields in the order they are set, which
+    /// is kept by [Point::tags] and [Point::fields].
+    names: Vec<String>,
+}
+
+#[pymethods]
+impl Point {
+    /// Build a [Point] from dicts of tags and fields, whose value types are
+    /// inferred from the python objects.
+    ///
+    /// The supported types are `bool`, `int`, `float`, `str` and `bytes`,
+    /// which are mapped to `Boolean`, `Int64`, `Double`, `String` and
+    /// `Varbinary`, and the tag values must be `str`. The names are checked in
+    /// the same way as [PointBuilder] without `strict`.
+    #[staticmethod]
+    pub fn from_dict(
+        table: String,
+        timestamp: TimestampMs,
+        tags: &PyDict,
+        fields: &PyDict,
+    ) -> PyResult<Point> {
+        let mut builder = RustPointBuilder::new(table).timestamp(timestamp);
+        let mut names = Vec::with_capacity(tags.len() + fields.len());
+        for (name, val) in tags {
+            let name: String = name.extract()?;
+            if !val.is_instance_of::<PyString>()? {
+                return Err(PyTypeError::new_err(format!(
+                    "tag value must be str, tag:{name}, type:{}",
+                    val.get_type().name()?
+                )));
+            }
+            record_name(&mut names, &name, false)?;
+            builder = builder.tag(name, RustValue::String(val.extract()?));
+        }
+        for (name, val) in fields {
+            let name: String = name.extract()?;
+            let val = infer_value(val)?.ok_or_else(|| {
+                PyTypeError::new_err(format!(
+                    "unsupported field value type, field:{name}, type:{}",
+                    val.get_type().name().unwrap_or("unknown")
+                ))
+            })?;
+            record_name(&mut names, &name, false)?;
+            builder = builder.field(name, val);
+        }
+
+        let rust_point = builder.build().map_err(PyTypeError::new_err)?;
+        Ok(Point { rust_point, names })
+    }
+
+    pub fn table(&self) -> &str {
+        &self.rust_point.table
+    }
+
+    pub fn timestamp(&self) -> TimestampMs {
+        self.rust_point.timestamp
+    }
+
+    /// The tags as a dict in the order they are set, whose values are
+    /// converted in the same way as [`Column::value`].
+    pub fn tags<'p>(&self, py: Python<'p>) -> PyResult<&'p PyDict> {
+        values_to_dict(py, &self.rust_point.tags, &self.names)
+    }
+
+    /// The fields as a dict in the order they are set, whose values are
+    /// converted in the same way as [`Column::value`].
+    pub fn fields<'p>(&self, py: Python<'p>) -> PyResult<&'p PyDict> {
+        values_to_dict(py, &self.rust_point.fields, &self.names)
+    }
+
+    pub fn __str__(&self) -> String {
+        format!("{:?}", self.rust_point)
+    }
+}
+
+/// Convert the `values` into a dict, whose keys are in the order of `names`.
+fn values_to_dict<'p>(
+    py: Python<'p>,
+    values: &BTreeMap<String, RustValue>,
+    names: &[String],
+) -> PyResult<&'p PyDict> {
+    let dict = PyDict::new(py);
+    for name in names {
+        if let Some(value) = values.get(name) {
+            dict.set_item(name, value_to_object(py, value))?;
+        }
+    }
+
+    Ok(dict)
+}
+
+fn is_datetime(py: Python<'_>, obj: &PyAny) -> PyResult<bool> {
+    let datetime_type = py
+        .import("datetime")?
+        .getattr("datetime")?
+        .downcast::<PyType>()?;
+    obj.is_instance(datetime_type)
+}
+
+/// Convert a `datetime.datetime` or `datetime.date` into the milliseconds since
+/// the epoch.
+///
+/// A naive datetime is assumed to be in UTC if `assume_utc` is set, otherwise
+/// in the local timezone, and a date is treated as its midnight.
+fn datetime_to_timestamp_ms(py: Python<'_>, dt: &PyAny, assume_utc: bool) -> PyResult<TimestampMs> {
+    let datetime = py.import("datetime")?;
+    let utc = datetime.getattr("timezone")?.getattr("utc")?;
+    let dt = if is_datetime(py, dt)? {
+        dt
+    } else {
+        let midnight = datetime.getattr("time")?.call0()?;
+        datetime
+            .getattr("datetime")?
+            .call_method1("combine", (dt, midnight))?
+    };
+    let dt = match (dt.getattr("tzinfo")?.is_none(), assume_utc) {
+        (true, true) => {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("tzinfo", utc)?;
+            dt.call_method("replace", (), Some(kwargs))?
+        }
+        // A naive datetime is treated as local time by `astimezone`.
+        (true, false) => dt.call_method0("astimezone")?,
+        (false, _) => dt,
+    };
+
+    // Compute with `timedelta` rather than `datetime.timestamp` to avoid the
+    // precision loss of float.
+    dt.call_method1("__sub__", (unix_epoch(py)?,))?
+        .call_method1("__floordiv__", (timedelta_ms(py, 1)?,))?
+        .extract()
+}
+
+/// Convert the milliseconds since the epoch into a UTC `datetime.datetime`.
+fn timestamp_ms_to_datetime(py: Python<'_>, timestamp: TimestampMs) -> PyResult<&PyAny> {
+    unix_epoch(py)?.call_method1("__add__", (timedelta_ms(py, timestamp)?,))
+}
+
+fn unix_epoch(py: Python<'_>) -> PyResult<&PyAny> {
+    let datetime = py.import("datetime")?;
+    let utc = datetime.getattr("timezone")?.getattr("utc")?;
+    datetime
+        .getattr("datetime")?
+        .call1((1970, 1, 1, 0, 0, 0, 0, utc))
+}
+
+fn timedelta_ms(py: Python<'_>, ms: i64) -> PyResult<&PyAny> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("milliseconds", ms)?;
+    py.import("datetime")?
+        .getattr("timedelta")?
+        .call((), Some(kwargs))
+}
+
+/// Infer the [RustValue] from the type of the python object, and `None` is
+/// returned if the type is not supported.
+fn infer_value(obj: &PyAny) -> PyResult<Option<RustValue>> {
+    // `bool` is a subclass of `int`, so it must be checked first.
+    let val = if obj.is_instance_of::<PyBool>()? {
+        RustValue::Boolean(obj.extract()?)
+    } else if obj.is_instance_of::<PyLong>()? {
+        RustValue::Int64(obj.extract()?)
+    } else if obj.is_instance_of::<PyFloat>()? {
+        RustValue::Double(obj.extract()?)
+    } else if obj.is_instance_of::<PyString>()? {
+        RustValue::String(obj.extract()?)
+    } else if let Some(bytes) = extract_bytes(obj)? {
+        RustValue::Varbinary(bytes)
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(val))
+}
+
+/// Copy the content of a `bytes`, `bytearray` or `memoryview` at once, and
+/// `None` is returned for other types.
+///
+/// The buffer protocol is not available in the limited api, so a contiguous
+/// `memoryview` is copied by assigning it to a writable `memoryview` over the
+/// returned buffer, and a non-contiguous one is copied into a `bytes` first.
+fn extract_bytes(obj: &PyAny) -> PyResult<Option<Vec<u8>>> {
+    if let Ok(bytes) = obj.downcast::<PyBytes>() {
+        return Ok(Some(bytes.as_bytes().to_vec()));
+    }
+    if let Ok(bytes) = obj.downcast::<PyByteArray>() {
+        return Ok(Some(bytes.to_vec()));
+    }
+
+    let py = obj.py();
+    let memoryview_type = py
+        .import("builtins")?
+        .getattr("memoryview")?
+        .downcast::<PyType>()?;
+    if !obj.is_instance(memoryview_type)? {
+        return Ok(None);
+    }
+    if !obj.getattr("c_contiguous")?.is_true()? {
+        let bytes = obj.call_method0("tobytes")?.downcast::<PyBytes>()?;
+        return Ok(Some(bytes.as_bytes().to_vec()));
+    }
+
+    // The same as `PyBUF_WRITE`, which is not exported in the limited api.
+    const BUF_WRITE: std::os::raw::c_int = 0x200;
+    let src = obj.call_method1("cast", ("B",))?;
+    let len: usize = src.len()?;
+    let mut buf = vec![0u8; len];
+    // SAFETY: the view is released before the `buf` is returned, so the memory
+    // is never accessed through it afterwards.
+    let dst: &PyAny = unsafe {
+        py.from_owned_ptr_or_err(pyo3::ffi::PyMemoryView_FromMemory(
+            buf.as_mut_ptr().cast(),
+            len as pyo3::ffi::Py_ssize_t,
+            BUF_WRITE,
+        ))?
+    };
+    let copied = dst.set_item(PySlice::new(py, 0, len as isize, 1), src);
+    dst.call_method0("release")?;
+    copied?;
+
+    Ok(Some(buf))
+}
+
+/// Convert the values of a numpy array into [RustValue]s according to its
+/// dtype, and `None` is returned if the dtype is not supported.
+fn values_from_numpy(array: &PyAny) -> PyResult<Option<Vec<RustValue>>> {
+    let dtype = array.getattr("dtype")?;
+    let kind: String = dtype.getattr("kind")?.extract()?;
+    let itemsize: usize = dtype.getattr("itemsize")?.extract()?;
+    // The nullable dtypes of pandas hold `pd.NA` for the missing values, which
+    // are replaced with `None` here, and the numpy dtypes have no `na_value`.
+    let list = if dtype.hasattr("na_value")? {
+        let kwargs = PyDict::new(array.py());
+        kwargs.set_item("dtype", "object")?;
+        kwargs.set_item("na_value", array.py().None())?;
+        array
+            .call_method("to_numpy", (), Some(kwargs))?
+            .call_method0("tolist")?
+    } else {
+        array.call_method0("tolist")?
+    };
+
+    macro_rules! convert {
+        ($rust_type:ty, $variant:ident) => {
+            list.extract::<Vec<Option<$rust_type>>>()?
+                .into_iter()
+                .map(|v| v.map_or(RustValue::Null, RustValue::$variant))
+                .collect()
+        };
+    }
+
+    let values = match (kind.as_str(), itemsize) {
+        ("b", _) => convert!(bool, Boolean),
+        ("i", 1) => convert!(i8, Int8),
+        ("i", 2) => convert!(i16, Int16),
+        ("i", 4) => convert!(i32, Int32),
+        ("i", 8) => convert!(i64, Int64),
+        ("u", 1) => convert!(u8, UInt8),
+        ("u", 2) => convert!(u16, UInt16),
+        ("u", 4) => convert!(u32, UInt32),
+        ("u", 8) => convert!(u64, UInt64),
+        ("f", 4) => convert!(f32, Float),
+        ("f", 8) => convert!(f64, Double),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(values))
+}
+
+/// Convert the values of a `pandas.Series` into [RustValue]s.
+///
+/// The types of the values in the series with a non-numeric dtype (e.g.
+/// `object`) are inferred one by one.
+fn values_from_series(series: &PyAny) -> PyResult<Vec<RustValue>> {
+    if let Some(values) = values_from_numpy(series)? {
+        return Ok(values);
+    }
+
+    let name: String = series.getattr("name")?.str()?.extract()?;
+    series
+        .call_method0("tolist")?
+        .iter()?
+        .map(|obj| {
+            let obj = obj?;
+            infer_value(obj)?.ok_or_else(|| {
+                PyTypeError::new_err(format!(
+                    "unsupported value type in dataframe, column:{name}, type:{}",
+                    obj.get_type().name().unwrap_or("unknown")
+                ))
+            })
+        })
+        .collect()
 }
 
 /// The builder for [Point].
@@ -476,53 +2115,144 @@ pub struct PointBuilder {
     /// The underlying builder defined in rust.
     ///
     /// The option is a workaround to use the builder pattern of the
-    /// `RustPointBuilder`, and it is ensured to be `Some` all the time.
+    /// `RustPointBuilder`, and it will be `None` after [PointBuilder::build]
+    /// is called, no matter whether the build succeeds.
     rust_builder: Option<RustPointBuilder>,
+    /// Whether to reject the name set before instead of overwriting it.
+    strict: bool,
+    /// The names of the tags and the fields in the order they are set.
+    names: Vec<String>,
+}
+
+impl PointBuilder {
+    fn take_builder(&mut self) -> PyResult<RustPointBuilder> {
+        self.rust_builder.take().ok_or_else(|| {
+            PyException::new_err(
+                "point builder has been consumed by build, please create a new one",
+            )
+        })
+    }
+
+    /// Set the tag or field named `name` by `set` after recording the name,
+    /// and the builder is kept unchanged if the name is rejected.
+    fn set_value(
+        &mut self,
+        name: String,
+        set: impl FnOnce(RustPointBuilder, String) -> RustPointBuilder,
+    ) -> PyResult<()> {
+        let builder = self.take_builder()?;
+        let recorded = record_name(&mut self.names, &name, self.strict);
+        self.rust_builder = Some(match recorded {
+            Ok(()) => set(builder, name),
+            Err(_) => builder,
+        });
+        recorded
+    }
+}
+
+/// Reject the empty name of a tag or field.
+fn check_name(name: &str) -> PyResult<()> {
+    if name.is_empty() {
+        return Err(PyValueError::new_err("tag or field name can't be empty"));
+    }
+    Ok(())
+}
+
+/// Check the name of the tag or field to set, and record it in the `names` if
+/// it is not set before, which is rejected in `strict` mode.
+fn record_name(names: &mut Vec<String>, name: &str, strict: bool) -> PyResult<()> {
+    check_name(name)?;
+    if names.iter().any(|v| v == name) {
+        if strict {
+            return Err(PyValueError::new_err(format!(
+                "tag or field name is set already, name:{name}"
+            )));
+        }
+    } else {
+        names.push(name.to_string());
+    }
+    Ok(())
 }
 
 #[pymethods]
 impl PointBuilder {
+    /// Create a builder for the point of the `table`.
+    ///
+    /// Setting a tag or field with a name set before overwrites it by default,
+    /// and a `ValueError` is raised instead if `strict` is set.
+    ///
+    /// The setters return the builder itself, so that the calls can be chained.
     #[new]
-    pub fn new(table: String) -> Self {
+    #[args(strict = "false")]
+    pub fn new(table: String, strict: bool) -> Self {
         Self {
             rust_builder: Some(RustPointBuilder::new(table)),
+            strict,
+            names: Vec::new(),
         }
     }
 
-    pub fn set_table(&mut self, table: String) {
-        let builder = self.rust_builder.take().unwrap().table(table);
-        self.rust_builder = Some(builder);
+    pub fn set_table(
+        mut slf: PyRefMut<'_, PointBuilder>,
+        table: String,
+    ) -> PyResult<PyRefMut<'_, PointBuilder>> {
+        let builder = slf.take_builder()?.table(table);
+        slf.rust_builder = Some(builder);
+        Ok(slf)
+    }
+
+    pub fn set_timestamp(
+        mut slf: PyRefMut<'_, PointBuilder>,
+        timestamp: TimestampMs,
+    ) -> PyResult<PyRefMut<'_, PointBuilder>> {
+        let builder = slf.take_builder()?.timestamp(timestamp);
+        slf.rust_builder = Some(builder);
+        Ok(slf)
     }
 
-    pub fn set_timestamp(&mut self, timestamp: TimestampMs) {
-        let builder = self.rust_builder.take().unwrap().timestamp(timestamp);
-        self.rust_builder = Some(builder);
+    /// Set the timestamp by a `datetime.datetime` or `datetime.date`, see
+    /// [ValueBuilder::timestamp_from_datetime] for the handling of timezone.
+    #[args(assume_utc = "true")]
+    pub fn set_timestamp_from_datetime<'p>(
+        slf: PyRefMut<'p, PointBuilder>,
+        py: Python<'p>,
+        dt: &PyAny,
+        assume_utc: bool,
+    ) -> PyResult<PyRefMut<'p, PointBuilder>> {
+        let timestamp = datetime_to_timestamp_ms(py, dt, assume_utc)?;
+        PointBuilder::set_timestamp(slf, timestamp)
     }
 
-    pub fn set_tag(&mut self, name: String, val: Value) {
-        let builder = self.rust_builder.take().unwrap().tag(name, val.raw_val);
-        self.rust_builder = Some(builder);
+    pub fn set_tag(
+        mut slf: PyRefMut<'_, PointBuilder>,
+        name: String,
+        val: Value,
+    ) -> PyResult<PyRefMut<'_, PointBuilder>> {
+        slf.set_value(name, |builder, name| builder.tag(name, val.raw_val))?;
+        Ok(slf)
     }
 
-    pub fn set_field(&mut self, name: String, val: Value) {
-        let builder = self.rust_builder.take().unwrap().field(name, val.raw_val);
-        self.rust_builder = Some(builder);
+    pub fn set_field(
+        mut slf: PyRefMut<'_, PointBuilder>,
+        name: String,
+        val: Value,
+    ) -> PyResult<PyRefMut<'_, PointBuilder>> {
+        slf.set_value(name, |builder, name| builder.field(name, val.raw_val))?;
+        Ok(slf)
     }
 
     pub fn build(&mut self) -> PyResult<Point> {
-        let rust_point = self
-            .rust_builder
-            .take()
-            .unwrap()
-            .build()
-            .map_err(PyTypeError::new_err)?;
+        let rust_point = self.take_builder()?.build().map_err(PyTypeError::new_err)?;
 
-        Ok(Point { rust_point })
+        Ok(Point {
+            rust_point,
+            names: std::mem::take(&mut self.names),
+        })
     }
 }
 
 /// A wrapper for `WriteRequestBuilder`.
-#[pyclass]
+#[pyclass(module = "horaedb_client")]
 #[derive(Clone, Debug, Default)]
 pub struct WriteRequest {
     rust_request: RustWriteRequest,
@@ -545,9 +2275,403 @@ impl WriteRequest {
         }
     }
 
+    /// Remove all the points, so that the request can be reused for the next
+    /// batch.
+    pub fn clear(&mut self) {
+        self.rust_request.point_groups.clear();
+    }
+
+    /// Remove the points sharing the table, the tags and the timestamp with a
+    /// later point, and return the number of the removed points.
+    ///
+    /// The kept points stay in their order, and the fields of the removed
+    /// points are not merged into them.
+    pub fn dedup(&mut self) -> usize {
+        let mut num_removed = 0;
+        for points in self.rust_request.point_groups.values_mut() {
+            let num_points = points.len();
+            dedup_points(points);
+            num_removed += num_points - points.len();
+        }
+        num_removed
+    }
+
+    /// Append all the points of `other` into this request.
+    pub fn merge(slf: &PyCell<WriteRequest>, other: &PyCell<WriteRequest>) {
+        // The `other` may be this request itself, so copy its points first.
+        let point_groups = other.borrow().rust_request.point_groups.clone();
+        slf.borrow_mut().extend(point_groups);
+    }
+
+    pub fn num_points(&self) -> usize {
+        self.rust_request.point_groups.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_points() == 0
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.num_points()
+    }
+
+    /// Split the request into the requests with at most `max_points` points
+    /// each, which contain all the points together.
+    pub fn split(&self, max_points: usize) -> PyResult<Vec<WriteRequest>> {
+        if max_points == 0 {
+            return Err(PyValueError::new_err("max points must be positive"));
+        }
+
+        Ok(self.split_by(|chunk, _| chunk.num_points() >= max_points))
+    }
+
+    /// Split the request into the requests whose estimated serialized sizes
+    /// are at most `max_bytes` each, to fit in the `max_send_msg_len` of the
+    /// [RpcConfig](crate::client::RpcConfig).
+    ///
+    /// The size is overestimated as the names are not deduplicated, and a
+    /// point larger than `max_bytes` is put in a request alone.
+    pub fn split_by_bytes(&self, max_bytes: usize) -> Vec<WriteRequest> {
+        let mut chunk_bytes = 0;
+        self.split_by(|chunk, point| {
+            let point_bytes = estimate_point_bytes(point);
+            let full = !chunk.is_empty() && chunk_bytes + point_bytes > max_bytes;
+            chunk_bytes = (if full { 0 } else { chunk_bytes }) + point_bytes;
+            full
+        })
+    }
+
+    /// Build a request containing all the points of the `reqs`.
+    #[staticmethod]
+    pub fn concat(reqs: Vec<PyRef<WriteRequest>>) -> WriteRequest {
+        let mut write_req = WriteRequest::default();
+        for req in reqs {
+            write_req.extend(req.rust_request.point_groups.clone());
+        }
+        write_req
+    }
+
+    /// Build a request from the dicts of the `records`, one point per record,
+    /// and the value types are inferred in the same way as
+    /// [`Point::from_dict`].
+    ///
+    /// A `KeyError` is raised if a record misses any of the keys, or its value
+    /// is `None`, unless the key is in the `optional_keys`. The timestamp is
+    /// always required.
+    #[staticmethod]
+    #[args(optional_keys = "None")]
+    pub fn from_records(
+        py: Python<'_>,
+        table: String,
+        records: Vec<&PyDict>,
+        timestamp_key: &str,
+        tag_keys: Vec<String>,
+        field_keys: Vec<String>,
+        optional_keys: Option<Vec<String>>,
+    ) -> PyResult<WriteRequest> {
+        let optional_keys: HashSet<_> = optional_keys.into_iter().flatten().collect();
+        let mut write_req = WriteRequest::default();
+        for (idx, record) in records.into_iter().enumerate() {
+            let get_value = |key: &str| -> PyResult<Option<&PyAny>> {
+                match record.get_item(key).filter(|v| !v.is_none()) {
+                    Some(v) => Ok(Some(v)),
+                    None if optional_keys.contains(key) => Ok(None),
+                    None => Err(PyKeyError::new_err(format!(
+                        "key not found in record, record:{idx}, key:{key}"
+                    ))),
+                }
+            };
+            let collect_values = |keys: &[String]| -> PyResult<&PyDict> {
+                let values = PyDict::new(py);
+                for key in keys {
+                    if let Some(v) = get_value(key)? {
+                        values.set_item(key, v)?;
+                    }
+                }
+                Ok(values)
+            };
+
+            let timestamp = record
+                .get_item(timestamp_key)
+                .filter(|v| !v.is_none())
+                .ok_or_else(|| {
+                    PyKeyError::new_err(format!(
+                        "timestamp not found in record, record:{idx}, key:{timestamp_key}"
+                    ))
+                })?
+                .extract()?;
+            let tags = collect_values(&tag_keys)?;
+            let fields = collect_values(&field_keys)?;
+            write_req.add_point(Point::from_dict(table.clone(), timestamp, tags, fields)?);
+        }
+
+        Ok(write_req)
+    }
+
+    /// Build a request from a `pandas.DataFrame`, one point per row.
+    ///
+    /// The values are converted column by column according to the dtypes, and
+    /// the timestamp column should be epoch milliseconds or `datetime64`.
+    #[staticmethod]
+    pub fn from_dataframe(
+        table: String,
+        df: &PyAny,
+        timestamp_col: &str,
+        tag_cols: Vec<String>,
+        field_cols: Vec<String>,
+    ) -> PyResult<WriteRequest> {
+        let df_columns = df.getattr("columns")?;
+        let get_column = |name: &str| -> PyResult<&PyAny> {
+            if df_columns.contains(name)? {
+                df.get_item(name)
+            } else {
+                Err(PyKeyError::new_err(format!(
+                    "column not found in dataframe, column:{name}"
+                )))
+            }
+        };
+        let column_values = |name: &String| -> PyResult<_> {
+            check_name(name)?;
+            let values = values_from_series(get_column(name)?)?;
+            Ok((name.clone(), values.into_iter()))
+        };
+
+        let mut timestamp_series = get_column(timestamp_col)?;
+        let timestamp_kind: String = timestamp_series
+            .getattr("dtype")?
+            .getattr("kind")?
+            .extract()?;
+        if timestamp_kind == "M" {
+            timestamp_series = timestamp_series
+                .call_method1("astype", ("datetime64[ms]",))?
+                .call_method1("astype", ("int64",))?;
+        }
+        let timestamps: Vec<TimestampMs> = timestamp_series.call_method0("tolist")?.extract()?;
+        let mut tags = tag_cols
+            .iter()
+            .map(column_values)
+            .collect::<PyResult<Vec<_>>>()?;
+        let mut fields = field_cols
+            .iter()
+            .map(column_values)
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let mut write_req = WriteRequest::default();
+        for timestamp in timestamps {
+            let mut builder = RustPointBuilder::new(table.clone()).timestamp(timestamp);
+            for (name, values) in &mut tags {
+                // All the columns of a dataframe have the same length.
+                builder = builder.tag(name.clone(), values.next().unwrap());
+            }
+            for (name, values) in &mut fields {
+                builder = builder.field(name.clone(), values.next().unwrap());
+            }
+            let rust_point = builder.build().map_err(PyTypeError::new_err)?;
+            write_req.rust_request.add_point(rust_point);
+        }
+
+        Ok(write_req)
+    }
+
+    /// Build a request from the lines of the InfluxDB line protocol, one point
+    /// per line.
+    ///
+    /// The timestamps are in the unit of the `precision`, which is one of
+    /// `ns`, `us`, `ms` and `s`, and the points without timestamp are given
+    /// the current time. A `ValueError` with the line number is raised if any
+    /// line is invalid.
+    #[staticmethod]
+    #[args(precision = "\"ms\"")]
+    pub fn from_line_protocol(lines: &str, precision: &str) -> PyResult<WriteRequest> {
+        let mut write_req = WriteRequest::default();
+        write_req
+            .rust_request
+            .add_points(line_protocol::parse_lines(lines, precision)?);
+        Ok(write_req)
+    }
+
     pub fn __str__(&self) -> PyResult<String> {
         Ok(format!("{:?}", self.rust_request))
     }
+
+    pub fn __richcmp__(&self, py: Python<'_>, other: &PyAny, op: CompareOp) -> PyObject {
+        let other = match other.extract::<PyRef<WriteRequest>>() {
+            Ok(other) => other,
+            Err(_) => return py.NotImplemented(),
+        };
+        let eq = self.rust_request.point_groups == other.rust_request.point_groups;
+        match op {
+            CompareOp::Eq => eq.into_py(py),
+            CompareOp::Ne => (!eq).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    /// The pickled state is the list of the points, and every value is kept
+    /// with its data type so that it is restored exactly.
+    pub fn __getstate__(&self, py: Python<'_>) -> Vec<PointState> {
+        let values_to_state = |values: &BTreeMap<String, RustValue>| {
+            values
+                .iter()
+                .map(|(name, value)| (name.clone(), value_to_state(py, value)))
+                .collect()
+        };
+        self.rust_request
+            .point_groups
+            .values()
+            .flatten()
+            .map(|point| {
+                (
+                    point.table.clone(),
+                    point.timestamp,
+                    values_to_state(&point.tags),
+                    values_to_state(&point.fields),
+                )
+            })
+            .collect()
+    }
+
+    pub fn __setstate__(
+        &mut self,
+        state: Vec<(String, TimestampMs, &PyAny, &PyAny)>,
+    ) -> PyResult<()> {
+        let values_from_state = |values: &PyAny| -> PyResult<BTreeMap<String, RustValue>> {
+            values
+                .extract::<Vec<(String, (u8, &PyAny))>>()?
+                .into_iter()
+                .map(|(name, value)| Ok((name, value_from_state(value)?)))
+                .collect()
+        };
+
+        let mut rust_request = RustWriteRequest::default();
+        for (table, timestamp, tags, fields) in state {
+            rust_request.add_point(RustPoint {
+                table,
+                timestamp,
+                tags: values_from_state(tags)?,
+                fields: values_from_state(fields)?,
+            });
+        }
+        self.rust_request = rust_request;
+
+        Ok(())
+    }
+}
+
+/// The pickled state of a [RustPoint], the table, the timestamp, the tags and
+/// the fields.
+type PointState = (
+    String,
+    TimestampMs,
+    Vec<(String, ValueState)>,
+    Vec<(String, ValueState)>,
+);
+
+impl WriteRequest {
+    /// Build a request from the `points`, which must all belong to the `table`
+    /// if it is given.
+    pub fn from_points(points: Vec<Point>, table: Option<&str>) -> PyResult<WriteRequest> {
+        let mut write_req = WriteRequest::default();
+        for point in points {
+            if let Some(table) = table {
+                if point.rust_point.table != table {
+                    return Err(PyValueError::new_err(format!(
+                        "point doesn't belong to the table, table:{table}, point_table:{}",
+                        point.rust_point.table
+                    )));
+                }
+            }
+            write_req.add_point(point);
+        }
+
+        Ok(write_req)
+    }
+
+    /// Split the points in order, and a new chunk is started before a point if
+    /// `is_full` returns true for the current chunk and it.
+    fn split_by(
+        &self,
+        mut is_full: impl FnMut(&WriteRequest, &RustPoint) -> bool,
+    ) -> Vec<WriteRequest> {
+        let mut chunks = Vec::new();
+        let mut chunk = WriteRequest::default();
+        for point in self.rust_request.point_groups.values().flatten() {
+            if is_full(&chunk, point) && !chunk.is_empty() {
+                chunks.push(std::mem::take(&mut chunk));
+            }
+            chunk.rust_request.add_point(point.clone());
+        }
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+
+        chunks
+    }
+
+    fn extend(&mut self, point_groups: HashMap<String, Vec<RustPoint>>) {
+        for points in point_groups.into_values() {
+            self.rust_request.add_points(points);
+        }
+    }
+}
+
+/// Keep only the last one of the `points` with the same tags and timestamp.
+fn dedup_points(points: &mut Vec<RustPoint>) {
+    let same_series = |a: &RustPoint, b: &RustPoint| {
+        a.timestamp == b.timestamp
+            && a.tags.len() == b.tags.len()
+            && a.tags
+                .iter()
+                .zip(&b.tags)
+                .all(|((a_name, a_val), (b_name, b_val))| {
+                    a_name == b_name && values_bitwise_eq(a_val, b_val)
+                })
+    };
+
+    // The indexes of the kept points grouped by the hash of their series.
+    let mut kept_by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut keep = vec![false; points.len()];
+    for (idx, point) in points.iter().enumerate().rev() {
+        let mut hasher = DefaultHasher::new();
+        point.timestamp.hash(&mut hasher);
+        for (name, value) in &point.tags {
+            name.hash(&mut hasher);
+            hash_value(value, &mut hasher);
+        }
+
+        let kept = kept_by_hash.entry(hasher.finish()).or_default();
+        if !kept.iter().any(|&i| same_series(&points[i], point)) {
+            kept.push(idx);
+            keep[idx] = true;
+        }
+    }
+
+    let mut keep = keep.into_iter();
+    points.retain(|_| keep.next().unwrap());
+}
+
+/// Estimate the serialized size of the `point` with the names and the values
+/// in full, plus a few bytes of the tags and the lengths for each of them.
+fn estimate_point_bytes(point: &RustPoint) -> usize {
+    const OVERHEAD_BYTES: usize = 4;
+    let value_bytes = |value: &RustValue| match value {
+        RustValue::Null => 0,
+        RustValue::Varbinary(v) => v.len(),
+        RustValue::String(v) => v.len(),
+        _ => 8,
+    };
+    let values_bytes = |values: &BTreeMap<String, RustValue>| -> usize {
+        values
+            .iter()
+            .map(|(name, value)| name.len() + value_bytes(value) + OVERHEAD_BYTES)
+            .sum()
+    };
+
+    point.table.len()
+        + std::mem::size_of::<TimestampMs>()
+        + OVERHEAD_BYTES
+        + values_bytes(&point.tags)
+        + values_bytes(&point.fields)
 }
 
 impl From<WriteRequest> for RustWriteRequest {
@@ -578,6 +2702,41 @@ impl WriteResponse {
         self.rust_response.failed
     }
 
+    #[getter]
+    pub fn success(&self) -> u32 {
+        self.rust_response.success
+    }
+
+    #[getter]
+    pub fn failed(&self) -> u32 {
+        self.rust_response.failed
+    }
+
+    /// The total number of the rows in the write, no matter whether they are
+    /// written successfully.
+    pub fn total(&self) -> u64 {
+        self.rust_response.success as u64 + self.rust_response.failed as u64
+    }
+
+    /// A response is true if none of the rows fails to be written.
+    pub fn __bool__(&self) -> bool {
+        self.rust_response.failed == 0
+    }
+
+    pub fn __richcmp__(&self, py: Python<'_>, other: &PyAny, op: CompareOp) -> PyObject {
+        let other = match other.extract::<PyRef<WriteResponse>>() {
+            Ok(other) => other,
+            Err(_) => return py.NotImplemented(),
+        };
+        let eq = self.rust_response.success == other.rust_response.success
+            && self.rust_response.failed == other.rust_response.failed;
+        match op {
+            CompareOp::Eq => eq.into_py(py),
+            CompareOp::Ne => (!eq).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
     pub fn __str__(&self) -> PyResult<String> {
         Ok(format!("{:?}", self.rust_response))
     }
@@ -590,3 +2749,813 @@ impl From<RustWriteResponse> for WriteResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(table: &str, timestamp: TimestampMs, tags: &[(&str, &str)], value: i64) -> RustPoint {
+        RustPoint {
+            table: table.to_string(),
+            timestamp,
+            tags: tags
+                .iter()
+                .map(|(name, val)| (name.to_string(), RustValue::String(val.to_string())))
+                .collect(),
+            fields: BTreeMap::from([("value".to_string(), RustValue::Int64(value))]),
+        }
+    }
+
+    fn values(points: &[RustPoint]) -> Vec<&RustValue> {
+        points.iter().map(|point| &point.fields["value"]).collect()
+    }
+
+    #[test]
+    fn test_dedup_points_last_wins() {
+        let mut points = vec![
+            point("t", 1, &[("host", "a")], 1),
+            point("t", 1, &[("host", "a")], 2),
+            point("t", 1, &[("host", "a")], 3),
+        ];
+        dedup_points(&mut points);
+        assert_eq!(values(&points), [&RustValue::Int64(3)]);
+    }
+
+    #[test]
+    fn test_dedup_points_different_series() {
+        let mut points = vec![
+            point("t", 1, &[("host", "a")], 1),
+            point("t", 1, &[("host", "b")], 2),
+            point("t", 1, &[("host", "a"), ("region", "x")], 3),
+            point("t", 1, &[("region", "a")], 4),
+            point("t", 1, &[], 5),
+            point("t", 2, &[("host", "a")], 6),
+        ];
+        dedup_points(&mut points);
+        assert_eq!(points.len(), 6);
+
+        // The tag values are compared with their types.
+        let mut points = vec![point("t", 1, &[], 1), point("t", 1, &[], 2)];
+        points[0]
+            .tags
+            .insert("id".to_string(), RustValue::String("1".to_string()));
+        points[1].tags.insert("id".to_string(), RustValue::Int64(1));
+        dedup_points(&mut points);
+        assert_eq!(points.len(), 2);
+    }
+
+    #[test]
+    fn test_dedup_points_keep_order() {
+        let mut points = vec![
+            point("t", 1, &[("host", "a")], 1),
+            point("t", 2, &[("host", "a")], 2),
+            point("t", 1, &[("host", "b")], 3),
+            point("t", 1, &[("host", "a")], 4),
+            point("t", 3, &[("host", "a")], 5),
+            point("t", 2, &[("host", "a")], 6),
+        ];
+        dedup_points(&mut points);
+        assert_eq!(
+            values(&points),
+            [3, 4, 5, 6]
+                .map(RustValue::Int64)
+                .iter()
+                .collect::<Vec<_>>()
+        );
+
+        let mut points = Vec::new();
+        dedup_points(&mut points);
+        assert!(points.is_empty());
+    }
+
+    #[test]
+    fn test_dedup_points_nan_tags() {
+        let mut points = vec![point("t", 1, &[], 1), point("t", 1, &[], 2)];
+        for point in &mut points {
+            point
+                .tags
+                .insert("x".to_string(), RustValue::Double(f64::NAN));
+        }
+        dedup_points(&mut points);
+        assert_eq!(values(&points), [&RustValue::Int64(2)]);
+    }
+
+    fn write_request(points: Vec<RustPoint>) -> WriteRequest {
+        let mut write_req = WriteRequest::default();
+        write_req.rust_request.add_points(points);
+        write_req
+    }
+
+    fn chunk_values(chunks: &[WriteRequest]) -> Vec<Vec<i64>> {
+        chunks
+            .iter()
+            .map(|chunk| {
+                chunk
+                    .rust_request
+                    .point_groups
+                    .values()
+                    .flatten()
+                    .map(|point| match point.fields["value"] {
+                        RustValue::Int64(v) => v,
+                        _ => unreachable!(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_point_bytes() {
+        // table + timestamp + overhead, "host" + "a" + overhead, "value" + i64 + overhead.
+        let point = point("t", 1, &[("host", "a")], 1);
+        assert_eq!(
+            estimate_point_bytes(&point),
+            (1 + 8 + 4) + (4 + 1 + 4) + (5 + 8 + 4)
+        );
+
+        let mut blob = point.clone();
+        blob.fields
+            .insert("blob".to_string(), RustValue::Varbinary(vec![0; 1000]));
+        blob.tags.insert("null".to_string(), RustValue::Null);
+        assert_eq!(
+            estimate_point_bytes(&blob),
+            estimate_point_bytes(&point) + (4 + 1000 + 4) + (4 + 4)
+        );
+    }
+
+    #[test]
+    fn test_split_by_bytes() {
+        let points: Vec<_> = (0..5).map(|i| point("t", i, &[], i)).collect();
+        let point_bytes = estimate_point_bytes(&points[0]);
+        let write_req = write_request(points);
+
+        // The chunks are filled up exactly without being split further.
+        let chunks = write_req.split_by_bytes(2 * point_bytes);
+        assert_eq!(chunk_values(&chunks), [vec![0, 1], vec![2, 3], vec![4]]);
+        let chunks = write_req.split_by_bytes(2 * point_bytes - 1);
+        assert_eq!(chunk_values(&chunks).len(), 5);
+        let chunks = write_req.split_by_bytes(5 * point_bytes);
+        assert_eq!(chunk_values(&chunks), [vec![0, 1, 2, 3, 4]]);
+    }
+
+    #[test]
+    fn test_split_by_bytes_oversized_point() {
+        let mut points: Vec<_> = (0..3).map(|i| point("t", i, &[], i)).collect();
+        let point_bytes = estimate_point_bytes(&points[0]);
+        points[1]
+            .fields
+            .insert("blob".to_string(), RustValue::Varbinary(vec![0; 1000]));
+        let write_req = write_request(points);
+
+        let chunks = write_req.split_by_bytes(2 * point_bytes);
+        assert_eq!(chunk_values(&chunks), [vec![0], vec![1], vec![2]]);
+
+        // A single point is kept even if it exceeds the limit.
+        let write_req = write_request(vec![point("t", 0, &[], 0)]);
+        assert_eq!(chunk_values(&write_req.split_by_bytes(1)), [vec![0]]);
+        assert_eq!(chunk_values(&write_req.split_by_bytes(0)), [vec![0]]);
+    }
+
+    #[test]
+    fn test_split_empty_request() {
+        let write_req = WriteRequest::default();
+        assert!(write_req.split_by_bytes(1024).is_empty());
+        assert!(write_req.split(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split() {
+        let write_req = write_request((0..5).map(|i| point("t", i, &[], i)).collect());
+        let chunks = write_req.split(2).unwrap();
+        assert_eq!(chunk_values(&chunks), [vec![0, 1], vec![2, 3], vec![4]]);
+        let chunks = write_req.split(5).unwrap();
+        assert_eq!(chunk_values(&chunks), [vec![0, 1, 2, 3, 4]]);
+        assert!(write_req.split(0).is_err());
+    }
+
+    #[test]
+    fn test_varbinary_large_blob() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let len = 16 << 20;
+            let blob: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let locals = PyDict::new(py);
+            locals.set_item("blob", PyBytes::new(py, &blob)).unwrap();
+            let eval = |expr: &str| py.eval(expr, None, Some(locals)).unwrap();
+            let varbinary = |val: &PyAny| match ValueBuilder.varbinary(val).unwrap().raw_val {
+                RustValue::Varbinary(v) => v,
+                v => panic!("unexpected value:{v:?}"),
+            };
+
+            for expr in ["blob", "bytearray(blob)", "memoryview(blob)"] {
+                assert!(varbinary(eval(expr)) == blob, "expr:{expr}");
+            }
+            // The content is read back identically through the python object.
+            let value = ValueBuilder.varbinary(eval("memoryview(blob)")).unwrap();
+            let obj = value_to_object(py, &value.raw_val);
+            assert_eq!(obj.extract::<Vec<u8>>(py).unwrap(), blob);
+
+            let sliced = eval("memoryview(blob)[3:1003]");
+            assert_eq!(varbinary(sliced), &blob[3..1003]);
+            let strided = eval("memoryview(blob)[1:100:3]");
+            let expected: Vec<_> = blob[1..100].iter().step_by(3).copied().collect();
+            assert_eq!(varbinary(strided), expected);
+            let shaped = eval("memoryview(blob)[:12].cast('B', (3, 4))");
+            assert_eq!(varbinary(shaped), &blob[..12]);
+            let wide = eval("memoryview(blob)[:16].cast('I')");
+            assert_eq!(varbinary(wide), &blob[..16]);
+            assert!(varbinary(eval("memoryview(b'')")).is_empty());
+
+            let released = eval("memoryview(blob)");
+            released.call_method0("release").unwrap();
+            assert!(ValueBuilder.varbinary(released).is_err());
+        });
+    }
+
+    #[test]
+    fn test_write_response_total() {
+        let resp = WriteResponse::from(RustWriteResponse::new(u32::MAX, u32::MAX));
+        assert_eq!(resp.total(), 2 * u32::MAX as u64);
+        let resp = WriteResponse::from(RustWriteResponse::new(3, 1));
+        assert_eq!(resp.total(), 4);
+    }
+
+    #[test]
+    fn test_value_builder_int_round_trip() {
+        type Build = fn(&ValueBuilder, &PyAny) -> PyResult<Value>;
+        type Expected = fn(i128) -> RustValue;
+        let cases: [(Build, i128, i128, Expected); 8] = [
+            (ValueBuilder::uint64, 0, u64::MAX as i128, |v| {
+                RustValue::UInt64(v as u64)
+            }),
+            (ValueBuilder::uint32, 0, u32::MAX as i128, |v| {
+                RustValue::UInt32(v as u32)
+            }),
+            (ValueBuilder::uint16, 0, u16::MAX as i128, |v| {
+                RustValue::UInt16(v as u16)
+            }),
+            (ValueBuilder::uint8, 0, u8::MAX as i128, |v| {
+                RustValue::UInt8(v as u8)
+            }),
+            (
+                ValueBuilder::int64,
+                i64::MIN as i128,
+                i64::MAX as i128,
+                |v| RustValue::Int64(v as i64),
+            ),
+            (
+                ValueBuilder::int32,
+                i32::MIN as i128,
+                i32::MAX as i128,
+                |v| RustValue::Int32(v as i32),
+            ),
+            (
+                ValueBuilder::int16,
+                i16::MIN as i128,
+                i16::MAX as i128,
+                |v| RustValue::Int16(v as i16),
+            ),
+            (ValueBuilder::int8, i8::MIN as i128, i8::MAX as i128, |v| {
+                RustValue::Int8(v as i8)
+            }),
+        ];
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for (build, min, max, expected) in cases {
+                for v in [min, 0, 1, max] {
+                    let obj = v.into_py(py);
+                    let value = build(&ValueBuilder, obj.as_ref(py)).unwrap();
+                    assert_eq!(value.raw_val, expected(v));
+                    let read_back = value_to_object(py, &value.raw_val);
+                    assert_eq!(read_back.extract::<i128>(py).unwrap(), v);
+                }
+                for v in [min - 1, max + 1] {
+                    let err = build(&ValueBuilder, v.into_py(py).as_ref(py))
+                        .err()
+                        .unwrap();
+                    assert!(err.is_instance_of::<PyOverflowError>(py), "value:{v}");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_values_from_nullable_series() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            // Mimic the `Int64` and `boolean` dtypes of pandas, whose `tolist`
+            // keeps the `pd.NA`.
+            let globals = PyDict::new(py);
+            py.run(
+                r#"
+class NA:
+    pass
+
+class Dtype:
+    na_value = NA()
+
+    def __init__(self, kind, itemsize):
+        self.kind, self.itemsize = kind, itemsize
+
+class Array(list):
+    def tolist(self):
+        return list(self)
+
+class Series(Array):
+    def __init__(self, values, kind, itemsize):
+        super().__init__(values)
+        self.dtype = Dtype(kind, itemsize)
+
+    def to_numpy(self, dtype, na_value):
+        assert dtype == "object"
+        return Array(na_value if v is Dtype.na_value else v for v in self)
+
+ints = Series([1, Dtype.na_value, -3], "i", 8)
+bools = Series([Dtype.na_value, True, False], "b", 1)
+"#,
+                Some(globals),
+                None,
+            )
+            .unwrap();
+
+            let values = |name: &str| values_from_numpy(globals.get_item(name).unwrap()).unwrap();
+            assert_eq!(
+                values("ints"),
+                Some(vec![
+                    RustValue::Int64(1),
+                    RustValue::Null,
+                    RustValue::Int64(-3)
+                ])
+            );
+            assert_eq!(
+                values("bools"),
+                Some(vec![
+                    RustValue::Null,
+                    RustValue::Boolean(true),
+                    RustValue::Boolean(false)
+                ])
+            );
+        });
+    }
+
+    fn rust_rows(col_names: &[&str], row_values: Vec<Vec<RustValue>>) -> Arc<Vec<RustRow>> {
+        let builder = RustRowBuilder {
+            col_idx_to_name: col_names.iter().map(|name| name.to_string()).collect(),
+            row_values,
+        };
+        Arc::new(builder.build())
+    }
+
+    #[test]
+    fn test_row_column_by_idx() {
+        let row = Row {
+            row_idx: 0,
+            rust_rows: rust_rows(
+                &["a", "b", "c"],
+                vec![vec![
+                    RustValue::Int64(1),
+                    RustValue::Int64(2),
+                    RustValue::Int64(3),
+                ]],
+            ),
+        };
+        let name = |idx: isize| row.column_by_idx(idx).map(|col| col.name().to_string());
+
+        assert_eq!(name(0).unwrap(), "a");
+        assert_eq!(name(2).unwrap(), "c");
+        assert_eq!(name(-1).unwrap(), "c");
+        assert_eq!(name(-3).unwrap(), "a");
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for idx in [3, 100, -4, -100, isize::MIN] {
+                let err = name(idx).unwrap_err();
+                assert!(err.is_instance_of::<PyIndexError>(py), "index:{idx}");
+            }
+        });
+    }
+
+    #[test]
+    fn test_point_builder_names() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let int64 = |v: i64| Value {
+                raw_val: RustValue::Int64(v),
+            };
+            let new_builder =
+                |strict: bool| Py::new(py, PointBuilder::new("t".to_string(), strict));
+            let set = |builder: &Py<PointBuilder>, method: &str, name: &str, v: i64| {
+                builder.call_method1(py, method, (name, int64(v).into_py(py)))
+            };
+
+            let builder = new_builder(true).unwrap();
+            for method in ["set_tag", "set_field"] {
+                let err = set(&builder, method, "", 1).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py), "method:{method}");
+            }
+            set(&builder, "set_field", "a", 1).unwrap();
+            for method in ["set_tag", "set_field"] {
+                let err = set(&builder, method, "a", 2).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py), "method:{method}");
+            }
+            // The builder is still usable after the names are rejected.
+            builder.call_method1(py, "set_timestamp", (1,)).unwrap();
+            let point = builder.borrow_mut(py).build().unwrap();
+            assert_eq!(point.names, ["a"]);
+            assert_eq!(point.rust_point.fields["a"], RustValue::Int64(1));
+            assert!(point.rust_point.tags.is_empty());
+
+            // The name is not recorded by the consumed builder.
+            assert!(set(&builder, "set_field", "b", 1).is_err());
+            assert!(builder.borrow(py).names.is_empty());
+
+            // The last one wins without `strict`.
+            let builder = new_builder(false).unwrap();
+            set(&builder, "set_field", "a", 1).unwrap();
+            set(&builder, "set_field", "a", 2).unwrap();
+            assert!(set(&builder, "set_field", "", 3).is_err());
+            builder.call_method1(py, "set_timestamp", (1,)).unwrap();
+            let point = builder.borrow_mut(py).build().unwrap();
+            assert_eq!(point.names, ["a"]);
+            assert_eq!(point.rust_point.fields["a"], RustValue::Int64(2));
+        });
+    }
+
+    #[test]
+    fn test_point_from_dict_empty_name() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let dict = |name: &str, v: &str| {
+                let dict = PyDict::new(py);
+                dict.set_item(name, v).unwrap();
+                dict
+            };
+            let point = Point::from_dict("t".to_string(), 1, dict("a", "x"), dict("b", "y"));
+            assert_eq!(point.unwrap().names, ["a", "b"]);
+
+            for (tags, fields) in [
+                (dict("", "x"), dict("b", "y")),
+                (dict("a", "x"), dict("", "y")),
+            ] {
+                let err = Point::from_dict("t".to_string(), 1, tags, fields).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py));
+            }
+        });
+    }
+
+    /// The values of every type, and a column of nulls.
+    fn all_type_rows() -> Arc<Vec<RustRow>> {
+        let col_names = [
+            "timestamp",
+            "double",
+            "float",
+            "varbinary",
+            "string",
+            "uint64",
+            "uint32",
+            "uint16",
+            "uint8",
+            "int64",
+            "int32",
+            "int16",
+            "int8",
+            "boolean",
+            "null",
+        ];
+        let row = |i: u8| {
+            vec![
+                RustValue::Timestamp(1_700_000_000_000 + i as i64),
+                RustValue::Double(0.5 + i as f64),
+                RustValue::Float(-1.5 - i as f32),
+                RustValue::Varbinary(vec![0, i, 255]),
+                RustValue::String(format!("s{i}")),
+                RustValue::UInt64(u64::MAX - i as u64),
+                RustValue::UInt32(u32::MAX - i as u32),
+                RustValue::UInt16(u16::MAX - i as u16),
+                RustValue::UInt8(u8::MAX - i),
+                RustValue::Int64(i64::MIN + i as i64),
+                RustValue::Int32(i32::MIN + i as i32),
+                RustValue::Int16(i16::MIN + i as i16),
+                RustValue::Int8(i8::MIN + i as i8),
+                RustValue::Boolean(i == 0),
+                RustValue::Null,
+            ]
+        };
+        rust_rows(
+            &col_names,
+            vec![row(0), vec![RustValue::Null; col_names.len()], row(1)],
+        )
+    }
+
+    #[test]
+    fn test_sql_query_response_bytes_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let round_trip = |resp: &SqlQueryResponse| {
+                let bytes = resp.to_bytes(py).unwrap();
+                SqlQueryResponse::from_bytes(py.get_type::<SqlQueryResponse>(), bytes.as_bytes())
+                    .unwrap()
+            };
+
+            let resp = SqlQueryResponse {
+                rust_rows: all_type_rows(),
+                affected_rows: 0,
+            };
+            let loaded = round_trip(&resp);
+            assert_eq!(loaded.rust_rows, resp.rust_rows);
+            assert_eq!(loaded.affected_rows, 0);
+
+            let resp = SqlQueryResponse {
+                rust_rows: Arc::new(Vec::new()),
+                affected_rows: 42,
+            };
+            let loaded = round_trip(&resp);
+            assert!(loaded.rust_rows.is_empty());
+            assert_eq!(loaded.affected_rows(), Some(42));
+
+            let data_type = py.get_type::<SqlQueryResponse>();
+            assert!(SqlQueryResponse::from_bytes(data_type, b"not arrow").is_err());
+        });
+    }
+
+    #[test]
+    fn test_arrow_column_round_trip() {
+        let rows = all_type_rows();
+        for (col_idx, col) in rows[0].columns().iter().enumerate() {
+            let col_values = rows.iter().map(|row| row.columns()[col_idx].value());
+            let (_, array) = make_arrow_column(col.name(), col_values.clone()).unwrap();
+            let values = arrow_column_values(&array).unwrap();
+            assert!(values.iter().eq(col_values), "column:{}", col.name());
+        }
+
+        // The values of a column must be of the same type.
+        let values = [RustValue::Int64(1), RustValue::Null, RustValue::Int32(1)];
+        assert!(make_arrow_column("mixed", values.iter()).is_err());
+    }
+
+    #[test]
+    fn test_format_csv_value() {
+        let format = |v: RustValue| format_csv_value(&v, false);
+        assert_eq!(format(RustValue::Null), "");
+        assert_eq!(format(RustValue::Varbinary(b"hello".to_vec())), "aGVsbG8=");
+        assert_eq!(format(RustValue::Varbinary(vec![])), "");
+        assert_eq!(format(RustValue::Double(0.5)), "0.5");
+        assert_eq!(format(RustValue::Int8(-1)), "-1");
+        assert_eq!(format(RustValue::Boolean(true)), "true");
+        assert_eq!(format(RustValue::String("a,b".to_string())), "a,b");
+
+        let timestamp = RustValue::Timestamp(1_700_000_000_123);
+        assert_eq!(format(timestamp.clone()), "2023-11-14T22:13:20.123Z");
+        assert_eq!(format_csv_value(&timestamp, true), "1700000000123");
+        assert_eq!(format(RustValue::Timestamp(0)), "1970-01-01T00:00:00.000Z");
+        assert_eq!(format(RustValue::Timestamp(-1)), "1969-12-31T23:59:59.999Z");
+        // The timestamp out of the range of the datetime is kept as is.
+        assert_eq!(format(RustValue::Timestamp(i64::MAX)), i64::MAX.to_string());
+    }
+
+    #[test]
+    fn test_push_csv_field() {
+        let push = |field: &str, delimiter: char| {
+            let mut csv = String::new();
+            push_csv_field(&mut csv, field, delimiter);
+            csv
+        };
+        assert_eq!(push("plain", ','), "plain");
+        assert_eq!(push("", ','), "");
+        assert_eq!(push("a,b", ','), "\"a,b\"");
+        assert_eq!(push("a,b", '\t'), "a,b");
+        assert_eq!(push("a\tb", '\t'), "\"a\tb\"");
+        assert_eq!(push("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+        assert_eq!(push("a\nb", ','), "\"a\nb\"");
+        assert_eq!(push("a\r\nb", ','), "\"a\r\nb\"");
+    }
+
+    #[test]
+    fn test_to_csv() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let resp = SqlQueryResponse {
+                rust_rows: rust_rows(
+                    &["ts", "name, full", "blob"],
+                    vec![
+                        vec![
+                            RustValue::Timestamp(0),
+                            RustValue::String("a \"b\"".to_string()),
+                            RustValue::Varbinary(vec![0xff]),
+                        ],
+                        vec![RustValue::Timestamp(1), RustValue::Null, RustValue::Null],
+                    ],
+                ),
+                affected_rows: 0,
+            };
+            let to_csv = |delimiter: char, header: bool, timestamp_as_ms: bool| {
+                resp.to_csv(py, None, delimiter, header, timestamp_as_ms)
+                    .unwrap()
+                    .unwrap()
+            };
+
+            assert_eq!(
+                to_csv(',', true, false),
+                "ts,\"name, full\",blob\n\
+                 1970-01-01T00:00:00.000Z,\"a \"\"b\"\"\",/w==\n\
+                 1970-01-01T00:00:00.001Z,,\n"
+            );
+            assert_eq!(to_csv(';', false, true), "0;\"a \"\"b\"\"\";/w==\n1;;\n");
+
+            let empty = SqlQueryResponse {
+                rust_rows: Arc::new(Vec::new()),
+                affected_rows: 0,
+            };
+            assert_eq!(
+                empty.to_csv(py, None, ',', true, false).unwrap().unwrap(),
+                ""
+            );
+        });
+    }
+
+    fn all_type_values() -> Vec<RustValue> {
+        vec![
+            RustValue::Null,
+            RustValue::Timestamp(-1),
+            RustValue::Double(f64::NAN),
+            RustValue::Double(-0.0),
+            RustValue::Float(f32::MAX),
+            RustValue::Varbinary(vec![0, 1, 255]),
+            RustValue::String("héllo".to_string()),
+            RustValue::UInt64(u64::MAX),
+            RustValue::UInt32(u32::MAX),
+            RustValue::UInt16(u16::MAX),
+            RustValue::UInt8(u8::MAX),
+            RustValue::Int64(i64::MIN),
+            RustValue::Int32(i32::MIN),
+            RustValue::Int16(i16::MIN),
+            RustValue::Int8(i8::MIN),
+            RustValue::Boolean(true),
+        ]
+    }
+
+    #[test]
+    fn test_value_state_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for value in all_type_values() {
+                let (code, obj) = value_to_state(py, &value);
+                let loaded = value_from_state((code, obj.as_ref(py))).unwrap();
+                assert!(values_bitwise_eq(&loaded, &value), "value:{value:?}");
+                assert_eq!(loaded.data_type(), value.data_type());
+            }
+
+            let obj = 1.to_object(py);
+            assert!(value_from_state((255, obj.as_ref(py))).is_err());
+            // The object must match the data type of the code.
+            let code = DataType::Boolean as u8;
+            assert!(value_from_state((code, "x".to_object(py).as_ref(py))).is_err());
+        });
+    }
+
+    #[test]
+    fn test_write_request_state_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut points: Vec<_> = (0..3).map(|i| point("t", i, &[("host", "a")], i)).collect();
+            points.push(point("other", 0, &[], 0));
+            for (idx, value) in all_type_values().into_iter().enumerate() {
+                points[0].fields.insert(format!("f{idx}"), value.clone());
+                points[3].tags.insert(format!("t{idx}"), value);
+            }
+            let write_req = write_request(points);
+
+            let state = write_req.__getstate__(py).into_py(py);
+            let mut loaded = WriteRequest::default();
+            loaded.__setstate__(state.extract(py).unwrap()).unwrap();
+
+            let (expected, actual) = (
+                &write_req.rust_request.point_groups,
+                &loaded.rust_request.point_groups,
+            );
+            assert_eq!(actual.len(), expected.len());
+            for (table, points) in expected {
+                let loaded_points = &actual[table];
+                assert_eq!(loaded_points.len(), points.len());
+                for (a, b) in points.iter().zip(loaded_points) {
+                    assert_eq!((&a.table, a.timestamp), (&b.table, b.timestamp));
+                    for (values, loaded_values) in [(&a.tags, &b.tags), (&a.fields, &b.fields)] {
+                        assert!(values.keys().eq(loaded_values.keys()));
+                        assert!(values
+                            .values()
+                            .zip(loaded_values.values())
+                            .all(|(a, b)| values_bitwise_eq(a, b)));
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_value_eq_and_hash() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let other_nan = f64::from_bits(f64::NAN.to_bits() | 1);
+            let raw_vals = [
+                RustValue::Double(f64::NAN),
+                RustValue::Double(other_nan),
+                RustValue::Double(0.0),
+                RustValue::Double(-0.0),
+                RustValue::Double(1.0),
+                RustValue::Float(f32::NAN),
+                RustValue::Float(0.0),
+                RustValue::Float(-0.0),
+                RustValue::Float(1.0),
+                RustValue::Int64(1),
+                RustValue::Int32(1),
+                RustValue::Boolean(true),
+                RustValue::Timestamp(1),
+                RustValue::Null,
+            ];
+            let objects: Vec<_> = raw_vals
+                .iter()
+                .map(|raw_val| {
+                    let value = Value {
+                        raw_val: raw_val.clone(),
+                    };
+                    Py::new(py, value).unwrap().into_ref(py).as_ref()
+                })
+                .collect();
+
+            for (i, a) in objects.iter().enumerate() {
+                // The copy of the value is equal to it with the same hash, even
+                // if it's a NaN.
+                let copy = Py::new(
+                    py,
+                    Value {
+                        raw_val: raw_vals[i].clone(),
+                    },
+                )
+                .unwrap();
+                assert!(a.eq(copy.as_ref(py)).unwrap(), "value:{a}");
+                assert_eq!(a.hash().unwrap(), copy.as_ref(py).hash().unwrap());
+
+                // The values are different from each other in type or bits.
+                for (j, b) in objects.iter().enumerate() {
+                    assert_eq!(a.eq(b).unwrap(), i == j, "a:{a}, b:{b}");
+                    assert_eq!(a.ne(b).unwrap(), i != j, "a:{a}, b:{b}");
+                }
+            }
+
+            // The values can be used as keys of a python dict.
+            let dict = PyDict::new(py);
+            for obj in &objects {
+                dict.set_item(obj, true).unwrap();
+            }
+            assert_eq!(dict.len(), objects.len());
+            let nan = Py::new(
+                py,
+                Value {
+                    raw_val: RustValue::Double(f64::NAN),
+                },
+            )
+            .unwrap();
+            assert!(dict.contains(nan).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_column_timestamp() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let rows = rust_rows(
+                &["ts", "v"],
+                vec![vec![
+                    RustValue::Timestamp(1_700_000_000_123),
+                    RustValue::Int64(1),
+                ]],
+            );
+            let column = |col_idx: usize| Column {
+                row_idx: 0,
+                col_idx,
+                rust_rows: rows.clone(),
+            };
+
+            let ts = column(0);
+            assert_eq!(ts.timestamp_ms().unwrap(), 1_700_000_000_123);
+            let datetime = ts.timestamp_datetime(py).unwrap();
+            assert!(datetime.eq(ts.as_datetime(py).unwrap()).unwrap());
+            let iso: String = datetime
+                .call_method0("isoformat")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(iso, "2023-11-14T22:13:20.123000+00:00");
+
+            let v = column(1);
+            for err in [
+                v.timestamp_ms().unwrap_err(),
+                v.timestamp_datetime(py).unwrap_err(),
+                v.as_datetime(py).unwrap_err(),
+            ] {
+                assert!(err.is_instance_of::<PyTypeError>(py));
+            }
+        });
+    }
+}
@@ -0,0 +1,436 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tools for building sql safely.
+
+use std::fmt::Write;
+
+use horaedb_client::model::value::Value as RustValue;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::model::Value;
+
+pub fn register_py_module(m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(escape_identifier, m)?)?;
+    m.add_function(wrap_pyfunction!(escape_literal, m)?)?;
+
+    Ok(())
+}
+
+/// Quote the `name` with backticks as a sql identifier, and the backticks
+/// inside are doubled.
+#[pyfunction]
+pub fn escape_identifier(name: &str) -> String {
+    quote(name, '`')
+}
+
+/// Format the `value` as a sql literal, the same as the params substituted by
+/// `SqlQueryRequest.with_params`.
+#[pyfunction]
+pub fn escape_literal(value: Value) -> PyResult<String> {
+    format_literal(&value.into())
+}
+
+/// Format the value as a sql literal.
+///
+/// The strings are quoted with the single quotes inside doubled and the
+/// backslashes escaped as the server takes them as escape chars in the MySQL
+/// dialect, the binaries are formatted as hex literals and the timestamps as
+/// milliseconds.
+pub fn format_literal(value: &RustValue) -> PyResult<String> {
+    let literal = match value {
+        RustValue::Null => "NULL".to_string(),
+        RustValue::Timestamp(v) => v.to_string(),
+        RustValue::Double(v) => format_float(*v)?,
+        RustValue::Float(v) => format_float(*v as f64)?,
+        RustValue::Varbinary(v) => {
+            let mut literal = String::with_capacity(v.len() * 2 + 3);
+            literal.push_str("X'");
+            for b in v {
+                write!(literal, "{b:02x}").unwrap();
+            }
+            literal.push('\'');
+            literal
+        }
+        RustValue::String(v) => quote(&v.replace('\\', "\\\\"), '\''),
+        RustValue::UInt64(v) => v.to_string(),
+        RustValue::UInt32(v) => v.to_string(),
+        RustValue::UInt16(v) => v.to_string(),
+        RustValue::UInt8(v) => v.to_string(),
+        RustValue::Int64(v) => v.to_string(),
+        RustValue::Int32(v) => v.to_string(),
+        RustValue::Int16(v) => v.to_string(),
+        RustValue::Int8(v) => v.to_string(),
+        RustValue::Boolean(v) => v.to_string(),
+    };
+
+    Ok(literal)
+}
+
+fn format_float(v: f64) -> PyResult<String> {
+    if v.is_finite() {
+        // The debug format always keeps the decimal point or the exponent, so
+        // the literal won't be taken as an integer.
+        Ok(format!("{v:?}"))
+    } else {
+        Err(PyValueError::new_err(format!(
+            "no sql literal for non-finite float, value:{v}"
+        )))
+    }
+}
+
+/// Quote the `s` with the `quote` char, and the `quote` chars inside are
+/// doubled.
+fn quote(s: &str, quote: char) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push(quote);
+    for c in s.chars() {
+        if c == quote {
+            quoted.push(quote);
+        }
+        quoted.push(c);
+    }
+    quoted.push(quote);
+    quoted
+}
+
+/// Replace the `?` placeholders in the `template` with the literals of the
+/// `params` in order.
+///
+/// The `?` in the quoted strings, the quoted identifiers and the comments are
+/// not taken as placeholders, and a `ValueError` is raised if the number of
+/// the placeholders doesn't match the number of the `params`.
+pub fn substitute_params(template: &str, params: &[RustValue]) -> PyResult<String> {
+    let mut sql = String::with_capacity(template.len());
+    let mut params_iter = params.iter();
+    let mut num_placeholders = 0;
+
+    for (segment, is_code) in split_code(template) {
+        if !is_code {
+            sql.push_str(segment);
+            continue;
+        }
+        for c in segment.chars() {
+            if c != '?' {
+                sql.push(c);
+                continue;
+            }
+            num_placeholders += 1;
+            if let Some(param) = params_iter.next() {
+                sql.push_str(&format_literal(param)?);
+            }
+        }
+    }
+
+    if num_placeholders != params.len() {
+        return Err(PyValueError::new_err(format!(
+            "number of params mismatches placeholders, placeholders:{num_placeholders}, \
+             params:{}",
+            params.len()
+        )));
+    }
+
+    Ok(sql)
+}
+
+/// Append the `LIMIT` and `OFFSET` clauses to the `sql` for fetching one page
+/// of the results.
+///
+/// A `ValueError` is raised if the `limit` is zero, or the `sql` has the
+/// `LIMIT` or `OFFSET` clause already, even in a subquery, because the page
+/// can't be applied correctly.
+pub fn paginate(sql: &str, limit: u64, offset: u64) -> PyResult<String> {
+    if limit == 0 {
+        return Err(PyValueError::new_err("limit must be positive"));
+    }
+
+    let has_paging = split_code(sql)
+        .into_iter()
+        .filter(|(_, is_code)| *is_code)
+        .flat_map(|(segment, _)| segment.split(|c: char| !c.is_ascii_alphanumeric() && c != '_'))
+        .any(|word| word.eq_ignore_ascii_case("LIMIT") || word.eq_ignore_ascii_case("OFFSET"));
+    if has_paging {
+        return Err(PyValueError::new_err(format!(
+            "sql has LIMIT or OFFSET already, sql:{sql}"
+        )));
+    }
+
+    // The `;` ending the statement is dropped, which may be followed by the
+    // comments, and the clauses are put on a new line in case the sql ends
+    // with a comment.
+    let mut sql = sql.to_string();
+    if let Some(idx) = find_terminator(&sql) {
+        sql.remove(idx);
+    }
+    Ok(format!("{}\nLIMIT {limit} OFFSET {offset}", sql.trim_end()))
+}
+
+/// Find the `;` ending the statement, after which there are only comments and
+/// whitespaces.
+fn find_terminator(sql: &str) -> Option<usize> {
+    let mut terminator = None;
+    let mut offset = 0;
+    for (segment, is_code) in split_code(sql) {
+        if is_code {
+            let trimmed = segment.trim_end();
+            if !trimmed.is_empty() {
+                terminator = trimmed.ends_with(';').then(|| offset + trimmed.len() - 1);
+            }
+        } else if !segment.starts_with("--") && !segment.starts_with("/*") {
+            // The quoted strings and identifiers are part of the statement.
+            terminator = None;
+        }
+        offset += segment.len();
+    }
+    terminator
+}
+
+/// Split the `sql` into the segments of code and the segments of the quoted
+/// strings, the quoted identifiers and the comments, and the flag is set for
+/// the segments of code.
+fn split_code(sql: &str) -> Vec<(&str, bool)> {
+    let mut segments = Vec::new();
+    let mut code_start = 0;
+    let mut chars = sql.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let end = match c {
+            '\'' | '"' | '`' => {
+                // A doubled quote char inside is just an escaped one, and it is
+                // handled as closing and reopening the quote. The backslash
+                // escapes the next char in the strings but not in the
+                // identifiers.
+                let mut escaped = false;
+                chars.by_ref().find(|(_, quoted)| {
+                    if escaped {
+                        escaped = false;
+                        return false;
+                    }
+                    escaped = c != '`' && *quoted == '\\';
+                    *quoted == c
+                })
+            }
+            '-' if matches!(chars.peek(), Some((_, '-'))) => {
+                chars.by_ref().find(|(_, commented)| *commented == '\n')
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                chars.next();
+                let mut prev = '\0';
+                chars.by_ref().find(|(_, commented)| {
+                    let end = prev == '*' && *commented == '/';
+                    prev = *commented;
+                    end
+                })
+            }
+            _ => continue,
+        };
+        let end = end.map_or(sql.len(), |(idx, c)| idx + c.len_utf8());
+
+        if code_start < start {
+            segments.push((&sql[code_start..start], true));
+        }
+        segments.push((&sql[start..end], false));
+        code_start = end;
+    }
+    if code_start < sql.len() {
+        segments.push((&sql[code_start..], true));
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_params() {
+        let sql = substitute_params(
+            "SELECT * FROM t WHERE a = ? AND b = ? AND c = ? AND d = ?",
+            &[
+                RustValue::Int64(-1),
+                RustValue::Double(1.0),
+                RustValue::Timestamp(1_700_000_000_000),
+                RustValue::Null,
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE a = -1 AND b = 1.0 AND c = 1700000000000 AND d = NULL"
+        );
+    }
+
+    #[test]
+    fn test_substitute_params_escape_quotes() {
+        let sql = substitute_params(
+            "SELECT * FROM t WHERE name = ? AND tag = ?",
+            &[
+                RustValue::String("it's".to_string()),
+                RustValue::String("a`b'; DROP TABLE t; --".to_string()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE name = 'it''s' AND tag = 'a`b''; DROP TABLE t; --'"
+        );
+    }
+
+    #[test]
+    fn test_substitute_params_escape_backslashes() {
+        // The backslash can't escape the closing quote to inject the sql.
+        let sql = substitute_params(
+            "SELECT * FROM t WHERE name = ? AND b = ?",
+            &[
+                RustValue::String("\\' OR 1=1 --".to_string()),
+                RustValue::String("a\\".to_string()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE name = '\\\\'' OR 1=1 --' AND b = 'a\\\\'"
+        );
+        let code: String = split_code(&sql)
+            .into_iter()
+            .filter_map(|(segment, is_code)| is_code.then_some(segment))
+            .collect();
+        assert_eq!(code, "SELECT * FROM t WHERE name =  AND b = ");
+    }
+
+    #[test]
+    fn test_split_code_backslash_escaped_quote() {
+        let sql = substitute_params(r"SELECT 'it\'s ?', ?", &[RustValue::Int32(1)]).unwrap();
+        assert_eq!(sql, r"SELECT 'it\'s ?', 1");
+        let sql = substitute_params(r#"SELECT "a\"?", ?"#, &[RustValue::Int32(1)]).unwrap();
+        assert_eq!(sql, r#"SELECT "a\"?", 1"#);
+
+        // The backslash is not an escape char in the quoted identifiers.
+        let sql = substitute_params(r"SELECT `a\` = ?", &[RustValue::Int32(1)]).unwrap();
+        assert_eq!(sql, r"SELECT `a\` = 1");
+    }
+
+    #[test]
+    fn test_substitute_params_skip_quoted_and_comments() {
+        let sql = substitute_params(
+            "SELECT '?', \"?\", `a?` FROM t WHERE a = ? -- b = ?\n/* ? */",
+            &[RustValue::String("?".to_string())],
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT '?', \"?\", `a?` FROM t WHERE a = '?' -- b = ?\n/* ? */"
+        );
+
+        // The doubled quote inside the literal doesn't close it.
+        let sql = substitute_params("SELECT 'it''s ?' = ?", &[RustValue::Int32(1)]).unwrap();
+        assert_eq!(sql, "SELECT 'it''s ?' = 1");
+    }
+
+    #[test]
+    fn test_substitute_params_mismatch() {
+        let too_few = substitute_params("SELECT ? + ?", &[RustValue::Int64(1)]);
+        assert!(too_few.is_err());
+
+        let too_many = substitute_params("SELECT ?", &[RustValue::Int64(1), RustValue::Int64(2)]);
+        assert!(too_many.is_err());
+
+        let no_placeholder = substitute_params("SELECT '?'", &[RustValue::Int64(1)]);
+        assert!(no_placeholder.is_err());
+
+        assert_eq!(substitute_params("SELECT 1", &[]).unwrap(), "SELECT 1");
+    }
+
+    #[test]
+    fn test_substitute_params_non_finite_float() {
+        assert!(substitute_params("SELECT ?", &[RustValue::Double(f64::NAN)]).is_err());
+    }
+
+    #[test]
+    fn test_escape_identifier() {
+        assert_eq!(escape_identifier("cpu"), "`cpu`");
+        assert_eq!(escape_identifier("a`b"), "`a``b`");
+        assert_eq!(escape_identifier("it's"), "`it's`");
+        assert_eq!(escape_identifier(""), "``");
+    }
+
+    #[test]
+    fn test_format_literal() {
+        let literal = |v: RustValue| format_literal(&v).unwrap();
+        assert_eq!(literal(RustValue::String("it's".to_string())), "'it''s'");
+        assert_eq!(literal(RustValue::String("a`b".to_string())), "'a`b'");
+        assert_eq!(literal(RustValue::String("a\\b".to_string())), "'a\\\\b'");
+        assert_eq!(literal(RustValue::Varbinary(vec![0x00, 0xab])), "X'00ab'");
+        assert_eq!(
+            literal(RustValue::Timestamp(1_700_000_000_000)),
+            "1700000000000"
+        );
+        assert_eq!(literal(RustValue::Float(0.5)), "0.5");
+        assert_eq!(literal(RustValue::Double(1e20)), "1e20");
+        assert_eq!(literal(RustValue::UInt64(u64::MAX)), "18446744073709551615");
+        assert_eq!(literal(RustValue::Boolean(true)), "true");
+        assert_eq!(literal(RustValue::Null), "NULL");
+        assert!(format_literal(&RustValue::Double(f64::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_paginate() {
+        assert_eq!(
+            paginate("SELECT * FROM t", 10, 20).unwrap(),
+            "SELECT * FROM t\nLIMIT 10 OFFSET 20"
+        );
+        assert_eq!(
+            paginate("SELECT * FROM t -- all rows", 10, 0).unwrap(),
+            "SELECT * FROM t -- all rows\nLIMIT 10 OFFSET 0"
+        );
+    }
+
+    #[test]
+    fn test_paginate_trailing_semicolon() {
+        assert_eq!(
+            paginate("SELECT * FROM t;  \n", 10, 0).unwrap(),
+            "SELECT * FROM t\nLIMIT 10 OFFSET 0"
+        );
+        assert_eq!(
+            paginate("SELECT * FROM t; -- all rows", 10, 0).unwrap(),
+            "SELECT * FROM t -- all rows\nLIMIT 10 OFFSET 0"
+        );
+        // The `;` inside the literal is kept.
+        assert_eq!(
+            paginate("SELECT * FROM t WHERE a = ';'", 10, 0).unwrap(),
+            "SELECT * FROM t WHERE a = ';'\nLIMIT 10 OFFSET 0"
+        );
+    }
+
+    #[test]
+    fn test_paginate_existing_paging() {
+        assert!(paginate("SELECT * FROM t LIMIT 5", 10, 0).is_err());
+        assert!(paginate("select * from t offset 5", 10, 0).is_err());
+        assert!(paginate("SELECT * FROM (SELECT * FROM t LIMIT 5)", 10, 0).is_err());
+
+        // The words in the literals, the identifiers and the comments are
+        // ignored.
+        assert!(paginate("SELECT `limit`, 'offset' FROM t -- LIMIT 5", 10, 0).is_ok());
+        assert!(paginate("SELECT limit_count FROM t", 10, 0).is_ok());
+    }
+
+    #[test]
+    fn test_paginate_zero_limit() {
+        assert!(paginate("SELECT * FROM t", 0, 0).is_err());
+        assert!(paginate("SELECT * FROM t", 1, 0).is_ok());
+    }
+}